@@ -0,0 +1,162 @@
+/**
+ * 音声処理設定
+ * 発話ごとの音声処理パイプラインと再生の設定を管理
+ */
+
+use serde::{Deserialize, Serialize};
+
+use super::jitter::JitterConfig;
+use super::meter::MeterConfig;
+use super::sink::SinkConfig;
+use super::trim::TrimConfig;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioConfig {
+    pub loudness: LoudnessConfig,
+    pub fade: FadeConfig,
+    /// 発話前後の無音の除去
+    pub trim: TrimConfig,
+    /// 再生開始前のバッファリング
+    pub jitter: JitterConfig,
+    /// 出力先（サウンドデバイス・ヌル・WAVファイル）
+    pub sink: SinkConfig,
+    /// 出力レベルの計測（アバターの口パクなど）
+    pub meter: MeterConfig,
+    /// 割り込みの読み上げを重ねるときに元の読み上げの音量を下げる
+    pub ducking: DuckingConfig,
+}
+
+/// ラウドネス正規化の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoudnessConfig {
+    pub enabled: bool,
+    /// 目標ラウドネス（LUFS）
+    pub target_lufs: f32,
+    /// トゥルーピークの上限（dBTP）
+    pub true_peak_db: f32,
+    /// 正規化で加減するゲインの上限（dB）
+    pub max_gain_db: f32,
+    /// 初期ゲインを決めるための先読み時間（ミリ秒）
+    pub lookahead_ms: u32,
+}
+
+impl Default for LoudnessConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            target_lufs: -16.0,
+            true_peak_db: -1.0,
+            max_gain_db: 20.0,
+            lookahead_ms: 200,
+        }
+    }
+}
+
+/// フェードイン・フェードアウトの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FadeConfig {
+    /// 発話開始時のフェードイン（ミリ秒）
+    pub fade_in_ms: u32,
+    /// 停止・スキップ時のフェードアウト（ミリ秒）
+    pub fade_out_ms: u32,
+    /// ソフトストップで単語の切れ目とみなす無音の長さ（ミリ秒）
+    pub soft_stop_silence_ms: u32,
+    /// ソフトストップで無音とみなすレベル（dBFS）
+    pub soft_stop_threshold_db: f32,
+    /// ソフトストップで切れ目を待つ最大時間（ミリ秒）
+    pub soft_stop_max_ms: u32,
+}
+
+impl Default for FadeConfig {
+    fn default() -> Self {
+        Self {
+            fade_in_ms: 10,
+            fade_out_ms: 40,
+            soft_stop_silence_ms: 80,
+            soft_stop_threshold_db: -40.0,
+            soft_stop_max_ms: 2000,
+        }
+    }
+}
+
+/// ダッキングの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DuckingConfig {
+    /// 下げる量（dB）
+    pub duck_db: f32,
+    /// 下げ始めてから下がり切るまで（ミリ秒）
+    pub attack_ms: u32,
+    /// 戻し始めてから戻り切るまで（ミリ秒）
+    pub release_ms: u32,
+}
+
+impl Default for DuckingConfig {
+    fn default() -> Self {
+        Self {
+            duck_db: -12.0,
+            attack_ms: 50,
+            release_ms: 300,
+        }
+    }
+}
+
+impl AudioConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let loudness = &self.loudness;
+        if !(-40.0..=-5.0).contains(&loudness.target_lufs) {
+            return Err("目標ラウドネスは-40〜-5 LUFSの範囲で指定してください".to_string());
+        }
+        if !(-9.0..=0.0).contains(&loudness.true_peak_db) {
+            return Err("トゥルーピーク上限は-9〜0 dBTPの範囲で指定してください".to_string());
+        }
+        if !(0.0..=30.0).contains(&loudness.max_gain_db) {
+            return Err("最大ゲインは0〜30 dBの範囲で指定してください".to_string());
+        }
+        if loudness.lookahead_ms > 1000 {
+            return Err("先読み時間は1000ミリ秒以下で指定してください".to_string());
+        }
+
+        let fade = &self.fade;
+        if fade.fade_in_ms > 1000 || fade.fade_out_ms > 1000 {
+            return Err("フェード時間は1000ミリ秒以下で指定してください".to_string());
+        }
+        if fade.soft_stop_max_ms > 10000 {
+            return Err("ソフトストップの待ち時間は10000ミリ秒以下で指定してください".to_string());
+        }
+
+        let trim = &self.trim;
+        if !(-90.0..=-10.0).contains(&trim.threshold_db) {
+            return Err("無音とみなすレベルは-90〜-10 dBFSの範囲で指定してください".to_string());
+        }
+        if trim.min_gap_ms > 2000 || trim.inter_utterance_gap_ms > 5000 {
+            return Err("無音の長さは最小2000ミリ秒・発話間5000ミリ秒以下で指定してください".to_string());
+        }
+
+        let jitter = &self.jitter;
+        if jitter.prebuffer_ms > 5000 || jitter.max_prebuffer_ms > 5000 {
+            return Err("バッファ量は5000ミリ秒以下で指定してください".to_string());
+        }
+
+        let meter = &self.meter;
+        if !(10..=1000).contains(&meter.interval_ms) {
+            return Err("レベル通知の間隔は10〜1000ミリ秒の範囲で指定してください".to_string());
+        }
+        if meter.full_open_db <= meter.silence_db {
+            return Err("口が全開になるレベルは無音とみなすレベルより大きくしてください".to_string());
+        }
+
+        let ducking = &self.ducking;
+        if !(-60.0..=0.0).contains(&ducking.duck_db) {
+            return Err("ダッキング量は-60〜0 dBの範囲で指定してください".to_string());
+        }
+        if ducking.attack_ms > 2000 || ducking.release_ms > 5000 {
+            return Err("ダッキングの時間はアタック2000ミリ秒・リリース5000ミリ秒以下で指定してください".to_string());
+        }
+
+        self.sink.validate()
+    }
+}
@@ -0,0 +1,404 @@
+/**
+ * 音声エフェクトチェーン
+ * デコード後の音声に順番にエフェクトを適用し、キャラクターボイスを作る
+ */
+
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+
+use super::biquad::Biquad;
+
+/// 残響テールの最大長（秒）
+const MAX_TAIL_SECONDS: f32 = 3.0;
+
+/// エフェクト設定
+/// JSONでは `{"type": "echo", "delay_ms": 250, ...}` の形式で指定する
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EffectConfig {
+    /// ピッチシフト（半音単位, -12〜12）
+    PitchShift { semitones: f32 },
+    /// エコー（フィードバック付きディレイ）
+    Echo { delay_ms: f32, feedback: f32, mix: f32 },
+    /// リバーブ（room_size, damping, mixは0.0〜1.0）
+    Reverb { room_size: f32, damping: f32, mix: f32 },
+    /// 3バンドイコライザー（各バンドのゲインをdBで指定）
+    Equalizer { low_db: f32, mid_db: f32, high_db: f32 },
+    /// リングモジュレーターによるロボット声
+    Robot { frequency_hz: f32, mix: f32 },
+    /// 電話風バンドパス（300Hz〜3400Hz）
+    Telephone,
+}
+
+trait Effect: Send {
+    fn process(&mut self, sample: f32) -> f32;
+
+    /// 入力が終わった後に出力が続くサンプル数
+    fn tail_samples(&self) -> usize {
+        0
+    }
+}
+
+/// 遅延線を使ったピッチシフター
+/// 半窓ずらした2つの読み出し位置をクロスフェードしながら速度を変えて読む
+struct PitchShift {
+    buffer: Vec<f32>,
+    write_pos: usize,
+    window: f32,
+    delay: f32,
+    rate: f32,
+}
+
+impl PitchShift {
+    fn new(sample_rate: f32, semitones: f32) -> Self {
+        let window = (sample_rate * 0.05).max(64.0);
+        Self {
+            buffer: vec![0.0; window as usize * 2 + 4],
+            write_pos: 0,
+            window,
+            delay: 1.0,
+            rate: 2f32.powf(semitones.clamp(-12.0, 12.0) / 12.0),
+        }
+    }
+
+    fn read(&self, delay: f32) -> f32 {
+        let len = self.buffer.len() as f32;
+        let position = (self.write_pos as f32 - delay).rem_euclid(len);
+        let index = position as usize;
+        let fraction = position - index as f32;
+        let next = (index + 1) % self.buffer.len();
+        self.buffer[index] * (1.0 - fraction) + self.buffer[next] * fraction
+    }
+}
+
+impl Effect for PitchShift {
+    fn process(&mut self, sample: f32) -> f32 {
+        self.buffer[self.write_pos] = sample;
+
+        let half = self.window / 2.0;
+        let delay_a = self.delay;
+        let delay_b = (self.delay - 1.0 + half).rem_euclid(self.window) + 1.0;
+        // 三角窓: 2つの読み出し位置のゲインの和は常に1
+        let gain_a = 1.0 - ((delay_a - 1.0) / self.window * 2.0 - 1.0).abs();
+        let output = self.read(delay_a) * gain_a + self.read(delay_b) * (1.0 - gain_a);
+
+        self.delay = (self.delay - 1.0 + (1.0 - self.rate)).rem_euclid(self.window) + 1.0;
+        self.write_pos = (self.write_pos + 1) % self.buffer.len();
+        output
+    }
+
+    fn tail_samples(&self) -> usize {
+        self.window as usize
+    }
+}
+
+struct Echo {
+    buffer: Vec<f32>,
+    position: usize,
+    feedback: f32,
+    mix: f32,
+}
+
+impl Echo {
+    fn new(sample_rate: f32, delay_ms: f32, feedback: f32, mix: f32) -> Self {
+        let delay = ((delay_ms.clamp(1.0, 2000.0) / 1000.0) * sample_rate) as usize;
+        Self {
+            buffer: vec![0.0; delay.max(1)],
+            position: 0,
+            feedback: feedback.clamp(0.0, 0.95),
+            mix: mix.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Effect for Echo {
+    fn process(&mut self, sample: f32) -> f32 {
+        let delayed = self.buffer[self.position];
+        self.buffer[self.position] = sample + delayed * self.feedback;
+        self.position = (self.position + 1) % self.buffer.len();
+        sample + delayed * self.mix
+    }
+
+    fn tail_samples(&self) -> usize {
+        // 残響が-60dBに減衰するまでの反復回数
+        let repeats = if self.feedback > 0.0 {
+            (0.001f32.ln() / self.feedback.ln()).ceil() as usize
+        } else {
+            1
+        };
+        self.buffer.len() * repeats.max(1)
+    }
+}
+
+/// ローパス付きフィードバックコムフィルター
+struct Comb {
+    buffer: Vec<f32>,
+    position: usize,
+    feedback: f32,
+    damping: f32,
+    filter_state: f32,
+}
+
+impl Comb {
+    fn process(&mut self, sample: f32) -> f32 {
+        let output = self.buffer[self.position];
+        self.filter_state = output * (1.0 - self.damping) + self.filter_state * self.damping;
+        self.buffer[self.position] = sample + self.filter_state * self.feedback;
+        self.position = (self.position + 1) % self.buffer.len();
+        output
+    }
+}
+
+struct AllPass {
+    buffer: Vec<f32>,
+    position: usize,
+}
+
+impl AllPass {
+    fn process(&mut self, sample: f32) -> f32 {
+        let buffered = self.buffer[self.position];
+        let output = buffered - sample;
+        self.buffer[self.position] = sample + buffered * 0.5;
+        self.position = (self.position + 1) % self.buffer.len();
+        output
+    }
+}
+
+/// Freeverb方式の簡易リバーブ
+struct Reverb {
+    combs: Vec<Comb>,
+    allpasses: Vec<AllPass>,
+    mix: f32,
+    sample_rate: f32,
+}
+
+impl Reverb {
+    // 44.1kHzでのFreeverbの遅延長
+    const COMB_TUNING: [usize; 4] = [1116, 1188, 1277, 1356];
+    const ALLPASS_TUNING: [usize; 2] = [556, 441];
+
+    fn new(sample_rate: f32, room_size: f32, damping: f32, mix: f32) -> Self {
+        let scale = sample_rate / 44100.0;
+        let feedback = 0.7 + room_size.clamp(0.0, 1.0) * 0.28;
+        let damping = damping.clamp(0.0, 1.0) * 0.4;
+        Self {
+            combs: Self::COMB_TUNING
+                .iter()
+                .map(|&len| Comb {
+                    buffer: vec![0.0; ((len as f32 * scale) as usize).max(1)],
+                    position: 0,
+                    feedback,
+                    damping,
+                    filter_state: 0.0,
+                })
+                .collect(),
+            allpasses: Self::ALLPASS_TUNING
+                .iter()
+                .map(|&len| AllPass {
+                    buffer: vec![0.0; ((len as f32 * scale) as usize).max(1)],
+                    position: 0,
+                })
+                .collect(),
+            mix: mix.clamp(0.0, 1.0),
+            sample_rate,
+        }
+    }
+}
+
+impl Effect for Reverb {
+    fn process(&mut self, sample: f32) -> f32 {
+        let input = sample * 0.015;
+        let mut wet: f32 = self.combs.iter_mut().map(|comb| comb.process(input)).sum();
+        for allpass in self.allpasses.iter_mut() {
+            wet = allpass.process(wet);
+        }
+        sample * (1.0 - self.mix) + wet * self.mix * 3.0
+    }
+
+    fn tail_samples(&self) -> usize {
+        (self.sample_rate * 1.5) as usize
+    }
+}
+
+struct Equalizer {
+    bands: [Biquad; 3],
+}
+
+impl Equalizer {
+    fn new(sample_rate: f32, low_db: f32, mid_db: f32, high_db: f32) -> Self {
+        Self {
+            bands: [
+                Biquad::low_shelf(sample_rate, 250.0, 0.707, low_db.clamp(-24.0, 24.0)),
+                Biquad::peaking(sample_rate, 1500.0, 0.9, mid_db.clamp(-24.0, 24.0)),
+                Biquad::high_shelf(sample_rate, 5000.0, 0.707, high_db.clamp(-24.0, 24.0)),
+            ],
+        }
+    }
+}
+
+impl Effect for Equalizer {
+    fn process(&mut self, sample: f32) -> f32 {
+        self.bands.iter_mut().fold(sample, |acc, band| band.process(acc))
+    }
+}
+
+struct Robot {
+    phase: f32,
+    phase_step: f32,
+    mix: f32,
+}
+
+impl Robot {
+    fn new(sample_rate: f32, frequency_hz: f32, mix: f32) -> Self {
+        Self {
+            phase: 0.0,
+            phase_step: 2.0 * PI * frequency_hz.clamp(1.0, 2000.0) / sample_rate,
+            mix: mix.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl Effect for Robot {
+    fn process(&mut self, sample: f32) -> f32 {
+        let modulated = sample * self.phase.sin();
+        self.phase = (self.phase + self.phase_step) % (2.0 * PI);
+        sample * (1.0 - self.mix) + modulated * self.mix
+    }
+}
+
+struct Telephone {
+    filters: [Biquad; 4],
+}
+
+impl Telephone {
+    fn new(sample_rate: f32) -> Self {
+        // 2段ずつ重ねて帯域外を急峻に落とす
+        Self {
+            filters: [
+                Biquad::high_pass(sample_rate, 300.0, 0.707),
+                Biquad::high_pass(sample_rate, 300.0, 0.707),
+                Biquad::low_pass(sample_rate, 3400.0, 0.707),
+                Biquad::low_pass(sample_rate, 3400.0, 0.707),
+            ],
+        }
+    }
+}
+
+impl Effect for Telephone {
+    fn process(&mut self, sample: f32) -> f32 {
+        let filtered = self.filters.iter_mut().fold(sample, |acc, filter| filter.process(acc));
+        // 軽い歪みで受話器らしさを出す
+        (filtered * 1.5).tanh()
+    }
+}
+
+/// 複数のエフェクトを順番に適用するチェーン
+pub struct EffectChain {
+    effects: Vec<Box<dyn Effect>>,
+    sample_rate: f32,
+}
+
+impl EffectChain {
+    pub fn new(configs: &[EffectConfig], sample_rate: u32) -> Self {
+        let sample_rate = sample_rate as f32;
+        let effects = configs
+            .iter()
+            .map(|config| -> Box<dyn Effect> {
+                match *config {
+                    EffectConfig::PitchShift { semitones } => {
+                        Box::new(PitchShift::new(sample_rate, semitones))
+                    }
+                    EffectConfig::Echo { delay_ms, feedback, mix } => {
+                        Box::new(Echo::new(sample_rate, delay_ms, feedback, mix))
+                    }
+                    EffectConfig::Reverb { room_size, damping, mix } => {
+                        Box::new(Reverb::new(sample_rate, room_size, damping, mix))
+                    }
+                    EffectConfig::Equalizer { low_db, mid_db, high_db } => {
+                        Box::new(Equalizer::new(sample_rate, low_db, mid_db, high_db))
+                    }
+                    EffectConfig::Robot { frequency_hz, mix } => {
+                        Box::new(Robot::new(sample_rate, frequency_hz, mix))
+                    }
+                    EffectConfig::Telephone => Box::new(Telephone::new(sample_rate)),
+                }
+            })
+            .collect();
+        Self { effects, sample_rate }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if self.effects.is_empty() {
+            return;
+        }
+        for sample in samples.iter_mut() {
+            *sample = self
+                .effects
+                .iter_mut()
+                .fold(*sample, |acc, effect| effect.process(acc));
+        }
+    }
+
+    /// 入力終了後に残るエコーやリバーブの余韻を出力する
+    pub fn flush(&mut self) -> Vec<f32> {
+        let max_tail = (self.sample_rate * MAX_TAIL_SECONDS) as usize;
+        let tail = self
+            .effects
+            .iter()
+            .map(|effect| effect.tail_samples())
+            .sum::<usize>()
+            .min(max_tail);
+        let mut samples = vec![0.0; tail];
+        self.process(&mut samples);
+        samples
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effect_config_deserialize() {
+        let json = r#"[{"type": "echo", "delay_ms": 200, "feedback": 0.4, "mix": 0.5}, {"type": "telephone"}]"#;
+        let configs: Vec<EffectConfig> = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            configs,
+            vec![
+                EffectConfig::Echo { delay_ms: 200.0, feedback: 0.4, mix: 0.5 },
+                EffectConfig::Telephone,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_flat_equalizer_is_transparent() {
+        let mut chain = EffectChain::new(
+            &[EffectConfig::Equalizer { low_db: 0.0, mid_db: 0.0, high_db: 0.0 }],
+            44100,
+        );
+        let input: Vec<f32> = (0..1000).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+        let mut output = input.clone();
+        chain.process(&mut output);
+        for (a, b) in input.iter().zip(output.iter()) {
+            assert!((a - b).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_echo_flush_produces_tail() {
+        let mut chain = EffectChain::new(
+            &[EffectConfig::Echo { delay_ms: 100.0, feedback: 0.5, mix: 0.5 }],
+            44100,
+        );
+        let mut impulse = vec![0.0; 10];
+        impulse[0] = 1.0;
+        chain.process(&mut impulse);
+        let tail = chain.flush();
+        assert!(tail.iter().any(|s| s.abs() > 0.1));
+    }
+}
@@ -0,0 +1,156 @@
+/**
+ * 音声ファイルの書き出し
+ * 合成済みの音声をWAV・FLAC形式にエンコードする
+ */
+
+use anyhow::{anyhow, bail, Result};
+use flacenc::component::{BitRepr, Stream, StreamInfo};
+use flacenc::constant::MIN_BLOCK_SIZE;
+use flacenc::error::Verify;
+use flacenc::source::{Fill, FrameBuf};
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::io::Cursor;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Wav,
+    Flac,
+}
+
+impl ExportFormat {
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Wav => "audio/wav",
+            ExportFormat::Flac => "audio/flac",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Wav => "wav",
+            ExportFormat::Flac => "flac",
+        }
+    }
+}
+
+fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16
+}
+
+/// モノラルのf32音声を指定形式にエンコードする
+pub fn encode(samples: &[f32], sample_rate: u32, format: ExportFormat) -> Result<Vec<u8>> {
+    match format {
+        ExportFormat::Wav => encode_wav(samples, sample_rate),
+        ExportFormat::Flac => encode_flac(samples, sample_rate),
+    }
+}
+
+fn encode_wav(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut cursor = Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut cursor, spec)?;
+    for &sample in samples {
+        writer.write_sample(to_i16(sample))?;
+    }
+    writer.finalize()?;
+    Ok(cursor.into_inner())
+}
+
+/// 末尾の短いブロックもエンコーダーが扱える長さになるブロックサイズ（既定のサイズに近いもの）
+fn block_size_for(len: usize, preferred: usize) -> usize {
+    (MIN_BLOCK_SIZE..=preferred)
+        .rev()
+        .find(|&size| len.is_multiple_of(size) || len % size >= MIN_BLOCK_SIZE)
+        .unwrap_or(preferred)
+}
+
+/// FLACにエンコードする（末尾の短いブロックもそのままの長さで書き出し、合成した音声と同じ長さにする）
+fn encode_flac(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+    if samples.is_empty() {
+        bail!("書き出す音声がありません");
+    }
+    if samples.len() < MIN_BLOCK_SIZE {
+        bail!("音声が短すぎるためFLACに書き出せません（{}サンプル）", samples.len());
+    }
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|(_, e)| anyhow!("FLACエンコーダーの設定が不正です: {:?}", e))?;
+    let pcm: Vec<i32> = samples.iter().map(|&s| to_i16(s) as i32).collect();
+    let block_size = block_size_for(pcm.len(), config.block_size);
+
+    let mut md5 = Md5::new();
+    for &sample in &pcm {
+        md5.update((sample as i16).to_le_bytes());
+    }
+    let mut info = StreamInfo::new(sample_rate as usize, 1, 16)
+        .map_err(|e| anyhow!("FLACのストリームを作れません: {:?}", e))?;
+    info.set_md5_digest(&md5.finalize().into());
+    // 総サンプル数とブロックサイズは、フレームを足すたびに実際の長さから数えられる
+    let mut stream = Stream::with_stream_info(info);
+    for (number, block) in pcm.chunks(block_size).enumerate() {
+        let mut framebuf = FrameBuf::with_size(1, block.len())
+            .map_err(|e| anyhow!("FLACのブロックを作れません: {:?}", e))?;
+        framebuf
+            .fill_interleaved(block)
+            .map_err(|e| anyhow!("FLACのブロックを作れません: {:?}", e))?;
+        let frame = flacenc::encode_fixed_size_frame(&config, &framebuf, number, stream.stream_info())
+            .map_err(|e| anyhow!("FLACへのエンコードに失敗しました: {:?}", e))?;
+        stream.add_frame(frame);
+    }
+
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| anyhow!("FLACの書き出しに失敗しました: {:?}", e))?;
+    Ok(sink.as_slice().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_signal() -> Vec<f32> {
+        (0..10_000)
+            .map(|i| (i as f32 * 0.03).sin() * 0.6 + ((i * 7919) % 13) as f32 * 0.001)
+            .collect()
+    }
+
+    #[test]
+    fn test_flac_round_trip() {
+        let samples = test_signal();
+        let encoded = encode(&samples, 44100, ExportFormat::Flac).unwrap();
+
+        let mut reader = claxon::FlacReader::new(Cursor::new(encoded)).unwrap();
+        assert_eq!(reader.streaminfo().sample_rate, 44100);
+        assert_eq!(reader.streaminfo().samples, Some(samples.len() as u64));
+        let decoded: Vec<i32> = reader.samples().map(|s| s.unwrap()).collect();
+        let expected: Vec<i32> = samples.iter().map(|&s| to_i16(s) as i32).collect();
+        assert_eq!(decoded, expected);
+
+        // 末尾が最小のブロックより短くなる長さでも、そのままの長さで書き出す
+        let short = &samples[..4096 + 10];
+        let encoded = encode(short, 44100, ExportFormat::Flac).unwrap();
+        let mut reader = claxon::FlacReader::new(Cursor::new(encoded)).unwrap();
+        assert_eq!(reader.samples().count(), short.len());
+
+        assert!(encode(&[], 44100, ExportFormat::Flac).is_err());
+    }
+
+    #[test]
+    fn test_wav_round_trip() {
+        let samples = test_signal();
+        let encoded = encode(&samples, 44100, ExportFormat::Wav).unwrap();
+
+        let mut reader = hound::WavReader::new(Cursor::new(encoded)).unwrap();
+        let decoded: Vec<i16> = reader.samples::<i16>().map(|s| s.unwrap()).collect();
+        assert_eq!(decoded.len(), samples.len());
+    }
+}
@@ -0,0 +1,152 @@
+/**
+ * ジッターバッファ
+ * チャンクの到着間隔のゆらぎを観測し、再生開始前に溜めておく音声の量を調整する
+ */
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use super::playback::INPUT_SAMPLE_RATE;
+
+/// 到着の遅れのピーク値を発話ごとに減衰させる割合
+const JITTER_DECAY: f32 = 0.8;
+/// 目標バッファ量を遅れのピーク値の何倍にするか
+const JITTER_MARGIN: f32 = 2.0;
+
+/// ジッターバッファの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JitterConfig {
+    pub enabled: bool,
+    /// 再生開始前に溜める音声の長さ（ミリ秒, 適応時の下限）
+    pub prebuffer_ms: u32,
+    /// 到着のゆらぎに合わせて溜める量を増減するか
+    pub adaptive: bool,
+    /// 適応時に溜める量の上限（ミリ秒）
+    pub max_prebuffer_ms: u32,
+}
+
+impl Default for JitterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            prebuffer_ms: 100,
+            adaptive: true,
+            max_prebuffer_ms: 1000,
+        }
+    }
+}
+
+/// 再生バッファの統計
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlaybackStats {
+    /// 再生中にデータが尽きた回数
+    pub underruns: u64,
+    /// 現在の目標バッファ量（ミリ秒）
+    pub target_ms: u64,
+    /// 観測した到着の遅れ（ミリ秒）
+    pub jitter_ms: u64,
+}
+
+fn ms_to_samples(ms: u32) -> f32 {
+    ms as f32 * INPUT_SAMPLE_RATE as f32 / 1000.0
+}
+
+fn samples_to_ms(samples: f32) -> u64 {
+    (samples.max(0.0) * 1000.0 / INPUT_SAMPLE_RATE as f32) as u64
+}
+
+/// チャンクの到着を記録する発話ごとの状態
+#[derive(Default)]
+pub struct ArrivalClock {
+    last_arrival: Option<Instant>,
+    last_chunk: usize,
+}
+
+pub struct JitterBuffer {
+    config: JitterConfig,
+    /// 到着の遅れのピーク値（入力サンプル数）
+    jitter: f32,
+    underruns: u64,
+}
+
+impl JitterBuffer {
+    pub fn new(config: JitterConfig) -> Self {
+        Self { config, jitter: 0.0, underruns: 0 }
+    }
+
+    pub fn set_config(&mut self, config: JitterConfig) {
+        self.config = config;
+    }
+
+    /// 再生を始める（または再開する）のに必要なバッファ量（入力サンプル数）
+    pub fn target_samples(&self) -> usize {
+        if !self.config.enabled {
+            return 0;
+        }
+        let base = ms_to_samples(self.config.prebuffer_ms);
+        if !self.config.adaptive {
+            return base as usize;
+        }
+        let max = ms_to_samples(self.config.max_prebuffer_ms).max(base);
+        (self.jitter * JITTER_MARGIN).clamp(base, max) as usize
+    }
+
+    /// チャンクの到着を記録し、前のチャンクの長さに対する遅れを観測する
+    pub fn on_chunk(&mut self, clock: &mut ArrivalClock, samples: usize) {
+        let now = Instant::now();
+        if let Some(last) = clock.last_arrival {
+            let interval = now.duration_since(last).as_secs_f32() * INPUT_SAMPLE_RATE as f32;
+            let late = interval - clock.last_chunk as f32;
+            self.jitter = self.jitter.max(late);
+        }
+        clock.last_arrival = Some(now);
+        clock.last_chunk = samples;
+    }
+
+    /// 再生中にデータが尽きた
+    pub fn on_underrun(&mut self) {
+        self.underruns += 1;
+        if self.config.adaptive {
+            // 次からはこれまでより多く溜めてから再生する
+            self.jitter = self.jitter.max(self.target_samples() as f32);
+        }
+    }
+
+    /// 発話を再生し終えた（観測した遅れを少しずつ忘れる）
+    pub fn on_utterance_finished(&mut self) {
+        self.jitter *= JITTER_DECAY;
+    }
+
+    pub fn stats(&self) -> PlaybackStats {
+        PlaybackStats {
+            underruns: self.underruns,
+            target_ms: samples_to_ms(self.target_samples() as f32),
+            jitter_ms: samples_to_ms(self.jitter),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_grows_after_underrun() {
+        let mut jitter = JitterBuffer::new(JitterConfig::default());
+        let base = jitter.target_samples();
+        assert_eq!(base, ms_to_samples(100) as usize);
+
+        jitter.on_underrun();
+        let grown = jitter.target_samples();
+        assert!(grown > base);
+        assert!(grown <= ms_to_samples(1000) as usize);
+        assert_eq!(jitter.stats().underruns, 1);
+
+        // 問題なく再生が続けば元の量へ戻っていく
+        for _ in 0..20 {
+            jitter.on_utterance_finished();
+        }
+        assert_eq!(jitter.target_samples(), base);
+    }
+}
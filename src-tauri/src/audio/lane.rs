@@ -0,0 +1,465 @@
+/**
+ * 再生レーン
+ * レーンごとに発話を順番に再生し、停止・一時退避・再開を管理する
+ */
+
+use std::collections::VecDeque;
+use tokio::sync::broadcast;
+
+use super::config::FadeConfig;
+use super::jitter::{ArrivalClock, JitterBuffer};
+use super::playback::{AudioEvent, StopTarget, INPUT_SAMPLE_RATE};
+
+/// 再生位置イベントを発行する間隔（速度変換後のサンプル数, 約100ms）
+const PROGRESS_INTERVAL_SAMPLES: usize = INPUT_SAMPLE_RATE as usize / 10;
+
+/// 停止済み発話IDを覚えておく数（停止後に届いたチャンクを破棄するため）
+const CANCELLED_HISTORY: usize = 32;
+
+pub(super) fn ms_to_samples(ms: u32) -> f32 {
+    ms as f32 * INPUT_SAMPLE_RATE as f32 / 1000.0
+}
+
+pub(super) fn samples_to_ms(samples: usize) -> u64 {
+    samples as u64 * 1000 / INPUT_SAMPLE_RATE as u64
+}
+
+/// 全レーンで共有する再生設定と状態
+pub(super) struct Shared {
+    pub fade: FadeConfig,
+    pub jitter: JitterBuffer,
+    cancelled: VecDeque<String>,
+    events: broadcast::Sender<AudioEvent>,
+}
+
+impl Shared {
+    pub fn new(fade: FadeConfig, jitter: JitterBuffer, events: broadcast::Sender<AudioEvent>) -> Self {
+        Self {
+            fade,
+            jitter,
+            cancelled: VecDeque::new(),
+            events,
+        }
+    }
+
+    pub fn emit(&self, event: AudioEvent) {
+        // 購読者がいない場合のエラーは無視する
+        let _ = self.events.send(event);
+    }
+
+    pub fn is_cancelled(&self, id: &str) -> bool {
+        self.cancelled.iter().any(|cancelled| cancelled == id)
+    }
+
+    fn mark_cancelled(&mut self, id: &str) {
+        if self.cancelled.len() == CANCELLED_HISTORY {
+            self.cancelled.pop_front();
+        }
+        self.cancelled.push_back(id.to_string());
+    }
+}
+
+struct Utterance {
+    id: String,
+    // まだ出力していないサンプル（受け取る前に速度変換済み）
+    samples: VecDeque<f32>,
+    // 出力済みのサンプル数
+    played: usize,
+    // 受信済みのサンプル数
+    received: usize,
+    // 最後のチャンクを受信済みか
+    ended: bool,
+    started: bool,
+    // データが尽きてバッファが溜まるのを待っているか
+    buffering: bool,
+    // フェードインを始める位置（再開した発話は途中からフェードインする）
+    fade_in_start: usize,
+    arrival: ArrivalClock,
+    last_progress: usize,
+}
+
+impl Utterance {
+    fn new(id: String) -> Self {
+        Self {
+            id,
+            samples: VecDeque::new(),
+            played: 0,
+            received: 0,
+            ended: false,
+            started: false,
+            buffering: false,
+            fade_in_start: 0,
+            arrival: ArrivalClock::default(),
+            last_progress: 0,
+        }
+    }
+}
+
+/// フェードアウトし終えた発話の扱い
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FadeAction {
+    /// 破棄する
+    Cancel,
+    /// 一時退避し、後で続きから再開する
+    Suspend,
+}
+
+/// フェードアウト中の停止要求
+struct Fading {
+    ids: Vec<String>,
+    remaining: f32,
+    length: f32,
+    action: FadeAction,
+}
+
+/// 単語の切れ目を待っている停止要求
+struct SoftStop {
+    target: StopTarget,
+    quiet_samples: f32,
+    elapsed: f32,
+}
+
+pub(super) struct Lane {
+    pub name: String,
+    /// 再生中にほかのレーンの音量を下げるか
+    pub ducks_others: bool,
+    /// レーンの音量（全体の音量に掛ける）
+    pub volume: f32,
+    utterances: VecDeque<Utterance>,
+    /// 割り込みで一時退避した発話
+    suspended: Vec<Utterance>,
+    resample_position: f32,
+    fading: Option<Fading>,
+    soft_stop: Option<SoftStop>,
+}
+
+impl Lane {
+    pub fn new(name: &str, ducks_others: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            ducks_others,
+            volume: 1.0,
+            utterances: VecDeque::new(),
+            suspended: Vec::new(),
+            resample_position: 0.0,
+            fading: None,
+            soft_stop: None,
+        }
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.utterances.iter().chain(self.suspended.iter()).any(|u| u.id == id)
+    }
+
+    fn utterance_mut(&mut self, id: &str) -> Option<&mut Utterance> {
+        self.utterances
+            .iter_mut()
+            .chain(self.suspended.iter_mut())
+            .find(|u| u.id == id)
+    }
+
+    /// 発話を末尾に登録する
+    pub fn insert(&mut self, id: &str) {
+        self.utterances.push_back(Utterance::new(id.to_string()));
+    }
+
+    /// 発話に音声データを追加する
+    pub fn push(&mut self, id: &str, samples: Vec<f32>, shared: &mut Shared) {
+        if let Some(utterance) = self.utterance_mut(id) {
+            shared.jitter.on_chunk(&mut utterance.arrival, samples.len());
+            utterance.received += samples.len();
+            utterance.samples.extend(samples);
+        }
+    }
+
+    /// 発話の全データを受信済みとしてマークする
+    pub fn finish(&mut self, id: &str) {
+        if let Some(utterance) = self.utterance_mut(id) {
+            utterance.ended = true;
+        }
+    }
+
+    /// 再生できる音声データが残っていないか
+    pub fn is_idle(&self) -> bool {
+        self.utterances.iter().all(|u| u.samples.is_empty())
+    }
+
+    /// 音を出している最中か（バッファリング中は含めない）
+    pub fn is_playing(&self) -> bool {
+        self.utterances.front().is_some_and(|u| u.started && !u.buffering)
+    }
+
+    fn is_front_playing(&self) -> bool {
+        self.utterances.front().is_some_and(|u| u.started)
+    }
+
+    fn target_ids(&self, target: &StopTarget) -> Vec<String> {
+        match target {
+            StopTarget::All => self
+                .utterances
+                .iter()
+                .chain(self.suspended.iter())
+                .map(|u| u.id.clone())
+                .collect(),
+            StopTarget::Current(_) => self.utterances.front().map(|u| vec![u.id.clone()]).unwrap_or_default(),
+            StopTarget::Utterance(id) if self.contains(id) => vec![id.clone()],
+            StopTarget::Utterance(_) => Vec::new(),
+        }
+    }
+
+    /// フェードアウトしてから停止する
+    pub fn stop(&mut self, target: StopTarget, shared: &mut Shared) {
+        self.soft_stop = None;
+        let ids = self.target_ids(&target);
+        if ids.is_empty() {
+            return;
+        }
+        let length = ms_to_samples(shared.fade.fade_out_ms);
+        let front_targeted = self.utterances.front().is_some_and(|u| ids.contains(&u.id));
+        if !front_targeted || !self.is_front_playing() || length < 1.0 {
+            self.cancel(&ids, shared);
+            return;
+        }
+        // 既にフェードアウト中なら対象を追加し、一時退避の予定は破棄に切り替える
+        match self.fading.as_mut() {
+            Some(fading) => {
+                for id in ids {
+                    if !fading.ids.contains(&id) {
+                        fading.ids.push(id);
+                    }
+                }
+                fading.action = FadeAction::Cancel;
+            }
+            None => {
+                self.fading = Some(Fading { ids, remaining: length, length, action: FadeAction::Cancel });
+            }
+        }
+    }
+
+    /// 単語の切れ目（短い無音）まで再生してからフェードアウトする
+    pub fn soft_stop(&mut self, target: StopTarget, shared: &mut Shared) {
+        if !self.is_front_playing() {
+            self.stop(target, shared);
+            return;
+        }
+        self.soft_stop = Some(SoftStop {
+            target,
+            quiet_samples: 0.0,
+            elapsed: 0.0,
+        });
+    }
+
+    /// 発話をフェードアウトして一時退避する（見つからなければfalse）
+    pub fn suspend(&mut self, id: &str, shared: &mut Shared) -> bool {
+        if !self.utterances.iter().any(|u| u.id == id) {
+            return self.suspended.iter().any(|u| u.id == id);
+        }
+        if self.fading.is_some() {
+            // 停止のフェードアウト中はそちらを優先する
+            return true;
+        }
+        let length = ms_to_samples(shared.fade.fade_out_ms);
+        let is_front = self.utterances.front().is_some_and(|u| u.id == id);
+        if is_front && self.is_front_playing() && length >= 1.0 {
+            self.soft_stop = None;
+            self.fading = Some(Fading {
+                ids: vec![id.to_string()],
+                remaining: length,
+                length,
+                action: FadeAction::Suspend,
+            });
+        } else {
+            self.move_to_suspended(id, shared);
+        }
+        true
+    }
+
+    fn move_to_suspended(&mut self, id: &str, shared: &mut Shared) {
+        let Some(index) = self.utterances.iter().position(|u| u.id == id) else {
+            return;
+        };
+        if index == 0 {
+            self.resample_position = 0.0;
+        }
+        let mut utterance = self.utterances.remove(index).expect("index exists");
+        // 再開時は再びバッファが溜まるのを待ち、続きからフェードインする
+        utterance.started = false;
+        utterance.buffering = false;
+        utterance.fade_in_start = utterance.played;
+        self.suspended.push(utterance);
+        shared.emit(AudioEvent::Suspended { utterance_id: id.to_string() });
+    }
+
+    /// 一時退避した発話を再生中の発話の次に戻す（見つからなければfalse）
+    pub fn resume(&mut self, id: &str) -> bool {
+        let Some(index) = self.suspended.iter().position(|u| u.id == id) else {
+            return false;
+        };
+        let utterance = self.suspended.remove(index);
+        let position = if self.is_front_playing() { 1 } else { 0 };
+        self.utterances.insert(position, utterance);
+        true
+    }
+
+    /// 指定した発話を直ちに破棄する
+    fn cancel(&mut self, ids: &[String], shared: &mut Shared) {
+        let front_cancelled = self.utterances.front().is_some_and(|u| ids.contains(&u.id));
+        if front_cancelled {
+            self.resample_position = 0.0;
+        }
+        for id in ids {
+            if let Some(index) = self.utterances.iter().position(|u| &u.id == id) {
+                self.utterances.remove(index);
+            }
+            self.suspended.retain(|u| &u.id != id);
+            shared.mark_cancelled(id);
+            shared.emit(AudioEvent::Finished {
+                utterance_id: id.clone(),
+                cancelled: true,
+            });
+        }
+    }
+
+    /// フェードアウトと単語区切り待ちの停止要求を進める
+    fn advance_stop(&mut self, sample: f32, step: f32, shared: &mut Shared) -> f32 {
+        if let Some(soft_stop) = self.soft_stop.as_mut() {
+            let threshold = 10f32.powf(shared.fade.soft_stop_threshold_db / 20.0);
+            if sample.abs() < threshold {
+                soft_stop.quiet_samples += step;
+            } else {
+                soft_stop.quiet_samples = 0.0;
+            }
+            soft_stop.elapsed += step;
+            if soft_stop.quiet_samples >= ms_to_samples(shared.fade.soft_stop_silence_ms)
+                || soft_stop.elapsed >= ms_to_samples(shared.fade.soft_stop_max_ms)
+            {
+                let target = soft_stop.target.clone();
+                self.soft_stop = None;
+                self.stop(target, shared);
+            }
+        }
+
+        let Some(fading) = self.fading.as_mut() else {
+            return 1.0;
+        };
+        fading.remaining -= step;
+        if fading.remaining <= 0.0 {
+            let ids = std::mem::take(&mut fading.ids);
+            let action = fading.action;
+            self.fading = None;
+            match action {
+                FadeAction::Cancel => self.cancel(&ids, shared),
+                FadeAction::Suspend => {
+                    for id in &ids {
+                        self.move_to_suspended(id, shared);
+                    }
+                }
+            }
+            return 0.0;
+        }
+        fading.remaining / fading.length
+    }
+
+    pub fn next_sample(&mut self, step: f32, shared: &mut Shared) -> f32 {
+        loop {
+            let fade_in_length = ms_to_samples(shared.fade.fade_in_ms);
+            let target = shared.jitter.target_samples();
+            let Some(utterance) = self.utterances.front_mut() else {
+                self.fading = None;
+                self.soft_stop = None;
+                return 0.0;
+            };
+
+            // 再生開始前とデータが尽きた後は、目標量が溜まるか最後のチャンクが届くまで待つ
+            if !utterance.ended && (!utterance.started || utterance.buffering) {
+                if utterance.samples.len() < target.max(1) {
+                    return 0.0;
+                }
+                utterance.buffering = false;
+            }
+
+            if utterance.samples.is_empty() {
+                if utterance.ended {
+                    // 再生し終えた発話を取り除き、次の発話へ
+                    let finished = self.utterances.pop_front().expect("front exists");
+                    self.resample_position = 0.0;
+                    if let Some(fading) = self.fading.as_mut() {
+                        fading.ids.retain(|id| id != &finished.id);
+                        if fading.ids.is_empty() {
+                            self.fading = None;
+                        }
+                    }
+                    self.soft_stop = None;
+                    shared.jitter.on_utterance_finished();
+                    if finished.started {
+                        shared.emit(AudioEvent::Progress {
+                            utterance_id: finished.id.clone(),
+                            position_ms: samples_to_ms(finished.received),
+                            duration_ms: samples_to_ms(finished.received),
+                        });
+                    }
+                    shared.emit(AudioEvent::Finished {
+                        utterance_id: finished.id,
+                        cancelled: false,
+                    });
+                    continue;
+                }
+                // 再生中にデータが尽きた場合はアンダーランとして記録し、バッファが溜まるのを待つ
+                // 再生スレッドを止めないよう、ログはイベントを受け取った側で出す
+                if utterance.started {
+                    utterance.buffering = true;
+                    let utterance_id = utterance.id.clone();
+                    shared.jitter.on_underrun();
+                    let stats = shared.jitter.stats();
+                    shared.emit(AudioEvent::Underrun {
+                        utterance_id,
+                        underruns: stats.underruns,
+                        target_ms: stats.target_ms,
+                    });
+                }
+                return 0.0;
+            }
+
+            if !utterance.started {
+                utterance.started = true;
+                let id = utterance.id.clone();
+                shared.emit(AudioEvent::Started { utterance_id: id });
+                continue;
+            }
+
+            // 線形補間によるリサンプリング
+            let sample_index = self.resample_position as usize;
+            let fraction = self.resample_position - sample_index as f32;
+            let sample1 = utterance.samples.get(sample_index).copied().unwrap_or(0.0);
+            let sample2 = utterance.samples.get(sample_index + 1).copied().unwrap_or(sample1);
+            let mut interpolated = sample1 * (1.0 - fraction) + sample2 * fraction;
+
+            // 発話の冒頭（再開時は再開位置）をフェードインする
+            let position = (utterance.played - utterance.fade_in_start) as f32 + self.resample_position;
+            if position < fade_in_length {
+                interpolated *= position / fade_in_length;
+            }
+
+            // リサンプリング位置を進め、処理済みのサンプルをバッファから削除
+            self.resample_position += step;
+            let samples_to_remove = (self.resample_position as usize).min(utterance.samples.len());
+            if samples_to_remove > 0 {
+                utterance.samples.drain(..samples_to_remove);
+                utterance.played += samples_to_remove;
+                self.resample_position -= samples_to_remove as f32;
+            }
+
+            if utterance.played - utterance.last_progress >= PROGRESS_INTERVAL_SAMPLES {
+                utterance.last_progress = utterance.played;
+                let event = AudioEvent::Progress {
+                    utterance_id: utterance.id.clone(),
+                    position_ms: samples_to_ms(utterance.played),
+                    duration_ms: samples_to_ms(utterance.received),
+                };
+                shared.emit(event);
+            }
+
+            return interpolated * self.advance_stop(interpolated, step, shared);
+        }
+    }
+}
@@ -0,0 +1,340 @@
+/**
+ * ラウドネス測定と正規化
+ * ITU-R BS.1770 / EBU R128 に基づくラウドネス(LUFS)を測定し、
+ * 発話ごとに目標ラウドネスへゲインを合わせてトゥルーピークリミッターを通す
+ */
+
+use std::collections::VecDeque;
+
+use super::biquad::Biquad;
+use super::config::LoudnessConfig;
+
+/// 絶対ゲート（LUFS）
+const ABSOLUTE_GATE: f64 = -70.0;
+/// 相対ゲート（LU）
+const RELATIVE_GATE: f64 = -10.0;
+/// ゲイン追従の時定数（秒）
+const GAIN_SMOOTHING_SECONDS: f32 = 0.1;
+/// リミッターの先読み（秒）
+const LIMITER_LOOKAHEAD_SECONDS: f32 = 0.005;
+/// リミッターのリリース時定数（秒）
+const LIMITER_RELEASE_SECONDS: f32 = 0.05;
+
+fn mean_square_to_lufs(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.max(1e-12).log10()
+}
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// ゲート付き積分ラウドネスの測定器（モノラル）
+pub struct LoudnessMeter {
+    // Kウェイティング（高域シェルフ + 高域通過）
+    filters: [Biquad; 2],
+    hop_len: usize,
+    hop_sum: f64,
+    hop_count: usize,
+    // 直近4ホップ（100ms x 4 = 400ms）の平均二乗値
+    recent_hops: VecDeque<f64>,
+    // 絶対ゲートを通過したブロックの平均二乗値
+    blocks: Vec<f64>,
+    total_sum: f64,
+    total_count: usize,
+}
+
+impl LoudnessMeter {
+    pub fn new(sample_rate: u32) -> Self {
+        let sample_rate_f = sample_rate as f32;
+        Self {
+            filters: [
+                Biquad::high_shelf(sample_rate_f, 1681.97, std::f32::consts::FRAC_1_SQRT_2, 4.0),
+                Biquad::high_pass(sample_rate_f, 38.14, 0.5003),
+            ],
+            hop_len: (sample_rate / 10) as usize,
+            hop_sum: 0.0,
+            hop_count: 0,
+            recent_hops: VecDeque::with_capacity(4),
+            blocks: Vec::new(),
+            total_sum: 0.0,
+            total_count: 0,
+        }
+    }
+
+    pub fn add(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            let weighted = self.filters.iter_mut().fold(sample, |acc, f| f.process(acc)) as f64;
+            let square = weighted * weighted;
+            self.hop_sum += square;
+            self.hop_count += 1;
+            self.total_sum += square;
+            self.total_count += 1;
+
+            if self.hop_count == self.hop_len {
+                if self.recent_hops.len() == 4 {
+                    self.recent_hops.pop_front();
+                }
+                self.recent_hops.push_back(self.hop_sum / self.hop_count as f64);
+                self.hop_sum = 0.0;
+                self.hop_count = 0;
+
+                if self.recent_hops.len() == 4 {
+                    let block = self.recent_hops.iter().sum::<f64>() / 4.0;
+                    if mean_square_to_lufs(block) >= ABSOLUTE_GATE {
+                        self.blocks.push(block);
+                    }
+                }
+            }
+        }
+    }
+
+    /// ゲート付き積分ラウドネス（400msブロックが無い場合はNone）
+    pub fn integrated(&self) -> Option<f32> {
+        if self.blocks.is_empty() {
+            return None;
+        }
+        let ungated = self.blocks.iter().sum::<f64>() / self.blocks.len() as f64;
+        let threshold = mean_square_to_lufs(ungated) + RELATIVE_GATE;
+        let gated: Vec<f64> = self
+            .blocks
+            .iter()
+            .copied()
+            .filter(|&block| mean_square_to_lufs(block) >= threshold)
+            .collect();
+        if gated.is_empty() {
+            return None;
+        }
+        Some(mean_square_to_lufs(gated.iter().sum::<f64>() / gated.len() as f64) as f32)
+    }
+
+    /// 400msに満たない区間も含めた暫定ラウドネス（無音ならNone）
+    pub fn provisional(&self) -> Option<f32> {
+        if self.total_count == 0 {
+            return None;
+        }
+        let loudness = mean_square_to_lufs(self.total_sum / self.total_count as f64);
+        (loudness >= ABSOLUTE_GATE).then_some(loudness as f32)
+    }
+}
+
+/// トゥルーピークリミッター
+/// 4倍オーバーサンプリング相当の補間でサンプル間ピークを推定し、先読みしてゲインを下げる
+struct TruePeakLimiter {
+    ceiling: f32,
+    lookahead: usize,
+    release_coeff: f32,
+    envelope: f32,
+    // 補間用の直近の入力 [x(k-2), x(k-1), x(k)]
+    history: [f32; 3],
+    pending: bool,
+    // (サンプル, 必要ゲイン)
+    window: VecDeque<(f32, f32)>,
+    // 先読み区間内の必要ゲインの最小値を求める単調キュー (通し番号, ゲイン)
+    minimum: VecDeque<(usize, f32)>,
+    pushed: usize,
+    popped: usize,
+}
+
+impl TruePeakLimiter {
+    fn new(sample_rate: u32, ceiling_db: f32) -> Self {
+        let sample_rate = sample_rate as f32;
+        Self {
+            ceiling: db_to_linear(ceiling_db),
+            lookahead: ((sample_rate * LIMITER_LOOKAHEAD_SECONDS) as usize).max(1),
+            release_coeff: 1.0 - (-1.0 / (sample_rate * LIMITER_RELEASE_SECONDS)).exp(),
+            envelope: 1.0,
+            history: [0.0; 3],
+            pending: false,
+            window: VecDeque::new(),
+            minimum: VecDeque::new(),
+            pushed: 0,
+            popped: 0,
+        }
+    }
+
+    /// x(k-1)とx(k)の間のサンプル間ピーク（Catmull-Rom補間）
+    fn segment_peak(p0: f32, p1: f32, p2: f32, p3: f32) -> f32 {
+        [0.25f32, 0.5, 0.75]
+            .iter()
+            .map(|&t| {
+                let t2 = t * t;
+                let t3 = t2 * t;
+                (0.5 * (2.0 * p1
+                    + (-p0 + p2) * t
+                    + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+                    + (-p0 + 3.0 * p1 - 3.0 * p2 + p3) * t3))
+                    .abs()
+            })
+            .fold(p2.abs(), f32::max)
+    }
+
+    fn push_window(&mut self, sample: f32, peak: f32) {
+        let gain = if peak > self.ceiling { self.ceiling / peak } else { 1.0 };
+        while self.minimum.back().is_some_and(|&(_, g)| g >= gain) {
+            self.minimum.pop_back();
+        }
+        self.minimum.push_back((self.pushed, gain));
+        self.window.push_back((sample, gain));
+        self.pushed += 1;
+    }
+
+    fn pop_window(&mut self) -> Option<f32> {
+        let (sample, _) = self.window.pop_front()?;
+        while self.minimum.front().is_some_and(|&(index, _)| index < self.popped) {
+            self.minimum.pop_front();
+        }
+        let target = self.minimum.front().map(|&(_, g)| g).unwrap_or(1.0);
+        self.popped += 1;
+
+        if target < self.envelope {
+            self.envelope = target;
+        } else {
+            self.envelope += (target - self.envelope) * self.release_coeff;
+        }
+        Some(sample * self.envelope)
+    }
+
+    fn process(&mut self, sample: f32, output: &mut Vec<f32>) {
+        let [p0, p1, p2] = self.history;
+        if self.pending {
+            // 次のサンプルが届いたので x(k) のピークが確定する
+            let peak = Self::segment_peak(p0, p1, p2, sample);
+            self.push_window(p2, peak);
+        }
+        self.history = [p1, p2, sample];
+        self.pending = true;
+
+        while self.window.len() > self.lookahead {
+            if let Some(limited) = self.pop_window() {
+                output.push(limited);
+            }
+        }
+    }
+
+    fn flush(&mut self, output: &mut Vec<f32>) {
+        if self.pending {
+            let [p0, p1, p2] = self.history;
+            let peak = Self::segment_peak(p0, p1, p2, 0.0);
+            self.push_window(p2, peak);
+            self.pending = false;
+        }
+        while let Some(limited) = self.pop_window() {
+            output.push(limited);
+        }
+    }
+}
+
+/// 発話ごとのラウドネス正規化
+/// 先読みした区間のラウドネスで初期ゲインを決め、以降は積分ラウドネスに追従する
+pub struct LoudnessNormalizer {
+    meter: LoudnessMeter,
+    target_lufs: f32,
+    max_gain_db: f32,
+    lookahead_len: usize,
+    lookahead: VecDeque<f32>,
+    gain: Option<f32>,
+    target_gain: f32,
+    smoothing_coeff: f32,
+    limiter: TruePeakLimiter,
+}
+
+impl LoudnessNormalizer {
+    pub fn new(config: &LoudnessConfig, sample_rate: u32) -> Self {
+        Self {
+            meter: LoudnessMeter::new(sample_rate),
+            target_lufs: config.target_lufs,
+            max_gain_db: config.max_gain_db,
+            lookahead_len: (sample_rate as u64 * config.lookahead_ms as u64 / 1000) as usize,
+            lookahead: VecDeque::new(),
+            gain: None,
+            target_gain: 1.0,
+            smoothing_coeff: 1.0 - (-1.0 / (sample_rate as f32 * GAIN_SMOOTHING_SECONDS)).exp(),
+            limiter: TruePeakLimiter::new(sample_rate, config.true_peak_db),
+        }
+    }
+
+    fn update_target_gain(&mut self) {
+        let measured = self.meter.integrated().or_else(|| self.meter.provisional());
+        if let Some(measured) = measured {
+            let gain_db = (self.target_lufs - measured).clamp(-self.max_gain_db, self.max_gain_db);
+            self.target_gain = db_to_linear(gain_db);
+        }
+    }
+
+    fn emit(&mut self, sample: f32, output: &mut Vec<f32>) {
+        let gain = match self.gain {
+            Some(gain) => gain + (self.target_gain - gain) * self.smoothing_coeff,
+            None => self.target_gain,
+        };
+        self.gain = Some(gain);
+        self.limiter.process(sample * gain, output);
+    }
+
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        self.meter.add(samples);
+        self.update_target_gain();
+        self.lookahead.extend(samples.iter().copied());
+
+        let mut output = Vec::with_capacity(samples.len());
+        while self.lookahead.len() > self.lookahead_len {
+            if let Some(sample) = self.lookahead.pop_front() {
+                self.emit(sample, &mut output);
+            }
+        }
+        output
+    }
+
+    pub fn finish(&mut self) -> Vec<f32> {
+        let mut output = Vec::with_capacity(self.lookahead.len());
+        while let Some(sample) = self.lookahead.pop_front() {
+            self.emit(sample, &mut output);
+        }
+        self.limiter.flush(&mut output);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(amplitude: f32, seconds: f32) -> Vec<f32> {
+        (0..(44100.0 * seconds) as usize)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * 997.0 * i as f32 / 44100.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_meter_full_scale_sine() {
+        // 0dBFSの997Hz正弦波は約-3.01 LUFS
+        let mut meter = LoudnessMeter::new(44100);
+        meter.add(&sine(1.0, 2.0));
+        let loudness = meter.integrated().unwrap();
+        assert!((loudness + 3.01).abs() < 0.5, "loudness = {}", loudness);
+    }
+
+    #[test]
+    fn test_normalizer_reaches_target_within_true_peak() {
+        let config = LoudnessConfig {
+            enabled: true,
+            target_lufs: -16.0,
+            true_peak_db: -1.0,
+            max_gain_db: 30.0,
+            lookahead_ms: 200,
+        };
+        let mut normalizer = LoudnessNormalizer::new(&config, 44100);
+        let mut output = Vec::new();
+        for chunk in sine(0.05, 3.0).chunks(4410) {
+            output.extend(normalizer.process(chunk));
+        }
+        output.extend(normalizer.finish());
+        assert_eq!(output.len(), 44100 * 3);
+
+        let mut meter = LoudnessMeter::new(44100);
+        meter.add(&output);
+        let loudness = meter.integrated().unwrap();
+        assert!((loudness + 16.0).abs() < 1.0, "loudness = {}", loudness);
+        let peak = output.iter().fold(0.0f32, |acc, s| acc.max(s.abs()));
+        assert!(peak <= db_to_linear(-1.0) + 1e-3, "peak = {}", peak);
+    }
+}
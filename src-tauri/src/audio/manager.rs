@@ -4,227 +4,416 @@
  */
 
 use anyhow::Result;
-use crossbeam_channel::{bounded, Receiver, Sender};
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender};
 use std::thread;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::collections::VecDeque;
-use cpal::StreamConfig;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use super::config::{DuckingConfig, FadeConfig};
+use super::jitter::{JitterConfig, PlaybackStats};
+use super::meter::MeterConfig;
+use super::playback::{AudioEvent, PlaybackState, StopTarget};
+use super::sink::{open_device_fallback, open_sink, open_sink_strict, AudioSink, RebuildReason, SinkConfig};
+use super::stretch::StretchFeeder;
+
+/// 出力先の状態を確認する間隔（ストリームエラーの有無を見るだけで、デバイスの問い合わせは各シンクが間隔を空けて行う）
+const DEVICE_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+/// 出力デバイスが失われてからヌルシンクに切り替えるまでの猶予
+const DEVICE_LOST_GRACE: Duration = Duration::from_secs(3);
 
 #[derive(Debug, Clone)]
 pub enum AudioCommand {
-    Play(Vec<f32>),
+    /// 発話を再生するレーンを指定する（Playより先に送る, 省略時は通常レーン）
+    Open { utterance_id: String, lane: String },
+    Play { utterance_id: String, samples: Vec<f32> },
+    Finish { utterance_id: String },
+    /// フェードアウトして全ての発話を停止
     Stop,
+    /// 単語の切れ目まで再生してから全ての発話を停止
+    SoftStop,
+    /// フェードアウトして指定したレーンで再生中の発話のみ停止
+    Skip { lane: String },
+    /// フェードアウトして指定した発話のみ停止
+    StopUtterance { utterance_id: String },
+    /// フェードアウトして発話を一時退避する
+    Suspend { utterance_id: String },
+    /// 一時退避した発話を続きから再生する
+    Resume { utterance_id: String },
     SetVolume(f32),
+    /// 再生速度（ピッチを保ったまま変更）
+    SetSpeed(f32),
+    /// レーンの音量と、全体の再生速度に掛ける倍率
+    SetLane { lane: String, volume: f32, speed: f32 },
+    /// 全レーンの一時停止・再開
+    SetPaused(bool),
+    SetFade(FadeConfig),
+    SetDucking(DuckingConfig),
+    SetMeter(MeterConfig),
+    SetJitter(JitterConfig),
+    /// 出力先を切り替える（再生中の発話は引き継ぐ）
+    SetSink(SinkConfig),
 }
 
 pub struct AudioManager {
     command_sender: Sender<AudioCommand>,
+    event_sender: broadcast::Sender<AudioEvent>,
+    playback: Arc<Mutex<PlaybackState>>,
+    /// 音声再生スレッドが動いているか
+    alive: Arc<AtomicBool>,
+}
+
+/// 音声再生スレッドの生存と発話の登録を確かめる（AudioManagerのロックを持たずに使う）
+#[derive(Clone)]
+pub struct PlaybackMonitor {
+    playback: Arc<Mutex<PlaybackState>>,
+    alive: Arc<AtomicBool>,
+}
+
+impl PlaybackMonitor {
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Acquire)
+    }
+
+    /// 発話がまだ登録されているか（再生待ち・再生中・一時退避中）
+    pub fn has_utterance(&self, utterance_id: &str) -> bool {
+        self.playback.lock().map(|state| state.contains(utterance_id)).unwrap_or(false)
+    }
+}
+
+/// スレッドが終了・パニックしたら生存フラグを下ろす
+struct AliveGuard(Arc<AtomicBool>);
+
+impl Drop for AliveGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
 }
 
 impl AudioManager {
-    pub fn new() -> Result<Self> {
+    pub fn new(sink_config: SinkConfig) -> Result<Self> {
         let (command_sender, command_receiver) = bounded::<AudioCommand>(100);
-        
+        let (event_sender, _) = broadcast::channel::<AudioEvent>(256);
+
         // 音声再生スレッドを起動
+        let events = event_sender.clone();
+        let playback = Arc::new(Mutex::new(PlaybackState::new(event_sender.clone())));
+        let thread_playback = playback.clone();
+        let alive = Arc::new(AtomicBool::new(true));
+        let guard = AliveGuard(alive.clone());
         thread::spawn(move || {
-            if let Err(e) = Self::audio_thread(command_receiver) {
+            let _guard = guard;
+            if let Err(e) = Self::audio_thread(sink_config, command_receiver, events, thread_playback) {
                 log::error!("[AudioManager] Audio thread error: {}", e);
             }
         });
-        
-        Ok(Self { command_sender })
-    }
-    
-    fn audio_thread(command_receiver: Receiver<AudioCommand>) -> Result<()> {
-        use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-        
-        let host = cpal::default_host();
-        let device = host
-            .default_output_device()
-            .ok_or_else(|| anyhow::anyhow!("No output device found"))?;
-        
-        let config = device.default_output_config()?;
-        let sample_rate = config.sample_rate().0;
-        let channels = config.channels();
-        
-        log::info!("[AudioManager] Output config: {} Hz, {} channels", sample_rate, channels);
-        log::info!("[AudioManager] Input format: 44100 Hz, 1 channel (mono), f32le");
-        log::info!("[AudioManager] Resample ratio: {:.3}", sample_rate as f32 / 44100.0);
-        
-        let buffer = Arc::new(Mutex::new(VecDeque::<f32>::new()));
-        let volume = Arc::new(Mutex::new(1.0f32));
-        let is_playing = Arc::new(Mutex::new(true));
-        
-        // コマンド受信スレッド
-        let buffer_clone = buffer.clone();
-        let volume_clone = volume.clone();
-        let is_playing_clone = is_playing.clone();
-        
-        thread::spawn(move || {
-            while let Ok(command) = command_receiver.recv() {
-                match command {
-                    AudioCommand::Play(audio_data) => {
-                        if let Ok(mut buf) = buffer_clone.lock() {
-                            buf.extend(audio_data);
+
+        Ok(Self { command_sender, event_sender, playback, alive })
+    }
+
+    /// 音声再生スレッドが動いているか
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::Acquire)
+    }
+
+    pub fn monitor(&self) -> PlaybackMonitor {
+        PlaybackMonitor { playback: self.playback.clone(), alive: self.alive.clone() }
+    }
+
+    /// 再生イベントを購読する
+    pub fn subscribe(&self) -> broadcast::Receiver<AudioEvent> {
+        self.event_sender.subscribe()
+    }
+
+    fn audio_thread(
+        mut sink_config: SinkConfig,
+        command_receiver: Receiver<AudioCommand>,
+        events: broadcast::Sender<AudioEvent>,
+        playback: Arc<Mutex<PlaybackState>>,
+    ) -> Result<()> {
+        // 開けない出力先（書き込めないWAVファイルなど）ではスレッドを終わらせず、無音出力で動かし続ける
+        let initial_sink = match open_sink(&sink_config, playback.clone()) {
+            Ok(sink) => sink,
+            Err(e) => {
+                log::error!("[AudioManager] Failed to open sink: {}, falling back to null sink", e);
+                open_sink(&SinkConfig::default_null(), playback.clone())?
+            }
+        };
+        let mut sink = Some(initial_sink);
+        let mut lost: Option<(Instant, RebuildReason)> = None;
+        let mut last_check = Instant::now();
+        let mut feeder = StretchFeeder::new();
+
+        // コマンドを処理する（送信側が破棄されるとシンクごと終了する）
+        loop {
+            match command_receiver.recv_timeout(DEVICE_CHECK_INTERVAL) {
+                Ok(AudioCommand::SetSink(config)) => {
+                    // 先に古いシンクを閉じてから開き直す（WAVファイルを同じパスに書き直す場合に備える）
+                    drop(sink.take());
+                    lost = None;
+                    let new_sink = match open_sink(&config, playback.clone()) {
+                        Ok(new_sink) => new_sink,
+                        Err(e) => {
+                            log::error!("[AudioManager] Failed to open sink: {}, falling back to null sink", e);
+                            open_sink(&SinkConfig::default_null(), playback.clone())?
                         }
-                        if let Ok(mut playing) = is_playing_clone.lock() {
-                            *playing = true;
+                    };
+                    Self::notify_device_changed(&events, new_sink.as_ref(), RebuildReason::ConfigChanged);
+                    sink = Some(new_sink);
+                    sink_config = config;
+                }
+                Ok(mut command) => {
+                    // 速度変換は重いため、再生バッファをロックする前に済ませる
+                    let mut tail = Vec::new();
+                    match &mut command {
+                        AudioCommand::Open { utterance_id, lane } => feeder.open(utterance_id, lane),
+                        AudioCommand::Play { utterance_id, samples } => {
+                            *samples = feeder.push(utterance_id, samples);
                         }
+                        AudioCommand::Finish { utterance_id } => tail = feeder.finish(utterance_id),
+                        AudioCommand::SetSpeed(speed) => feeder.set_speed(*speed),
+                        AudioCommand::SetLane { lane, speed, .. } => feeder.set_lane_speed(lane, *speed),
+                        _ => {}
                     }
-                    AudioCommand::Stop => {
-                        if let Ok(mut buf) = buffer_clone.lock() {
-                            buf.clear();
+
+                    let Ok(mut state) = playback.lock() else {
+                        break;
+                    };
+                    match command {
+                        AudioCommand::Open { utterance_id, lane } => {
+                            state.open(&utterance_id, &lane);
                         }
-                        if let Ok(mut playing) = is_playing_clone.lock() {
-                            *playing = false;
+                        AudioCommand::Play { utterance_id, samples } => {
+                            state.push(&utterance_id, samples);
                         }
-                    }
-                    AudioCommand::SetVolume(vol) => {
-                        if let Ok(mut v) = volume_clone.lock() {
-                            *v = vol.clamp(0.0, 1.0);
+                        AudioCommand::Finish { utterance_id } => {
+                            if !tail.is_empty() {
+                                state.push(&utterance_id, tail);
+                            }
+                            state.finish(&utterance_id);
+                        }
+                        AudioCommand::Stop => {
+                            state.stop(StopTarget::All);
+                        }
+                        AudioCommand::SoftStop => {
+                            state.soft_stop(StopTarget::All);
+                        }
+                        AudioCommand::Skip { lane } => {
+                            state.stop(StopTarget::Current(lane));
+                        }
+                        AudioCommand::StopUtterance { utterance_id } => {
+                            state.stop(StopTarget::Utterance(utterance_id));
+                        }
+                        AudioCommand::Suspend { utterance_id } => {
+                            state.suspend(&utterance_id);
+                        }
+                        AudioCommand::Resume { utterance_id } => {
+                            state.resume(&utterance_id);
+                        }
+                        AudioCommand::SetVolume(vol) => {
+                            state.set_volume(vol);
                         }
+                        AudioCommand::SetSpeed(_) => {}
+                        AudioCommand::SetLane { lane, volume, .. } => {
+                            state.set_lane(&lane, volume);
+                        }
+                        AudioCommand::SetPaused(paused) => {
+                            state.set_paused(paused);
+                        }
+                        AudioCommand::SetFade(fade) => {
+                            state.set_fade(fade);
+                        }
+                        AudioCommand::SetDucking(ducking) => {
+                            state.set_ducking(ducking);
+                        }
+                        AudioCommand::SetMeter(meter) => {
+                            state.set_meter(meter);
+                        }
+                        AudioCommand::SetJitter(jitter) => {
+                            state.set_jitter(jitter);
+                        }
+                        AudioCommand::SetSink(_) => {}
                     }
+                    // 停止・再生し終えた発話の変換器を破棄する
+                    feeder.retain(|id| state.contains(id));
                 }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
             }
-        });
-        
-        // 音声ストリームを作成
-        let stream = match config.sample_format() {
-            cpal::SampleFormat::F32 => Self::build_stream::<f32>(
-                &device,
-                &config.into(),
-                buffer,
-                volume,
-                is_playing,
-                sample_rate,
-                channels,
-            )?,
-            cpal::SampleFormat::I16 => Self::build_stream::<i16>(
-                &device,
-                &config.into(),
-                buffer,
-                volume,
-                is_playing,
-                sample_rate,
-                channels,
-            )?,
-            cpal::SampleFormat::U16 => Self::build_stream::<u16>(
-                &device,
-                &config.into(),
-                buffer,
-                volume,
-                is_playing,
-                sample_rate,
-                channels,
-            )?,
-            sample_format => {
-                return Err(anyhow::anyhow!("Unsupported sample format: {:?}", sample_format));
+
+            if last_check.elapsed() >= DEVICE_CHECK_INTERVAL {
+                last_check = Instant::now();
+                Self::check_sink(&sink_config, &mut sink, &mut lost, &playback, &events);
             }
-        };
-        
-        stream.play()?;
-        
-        // ストリームを維持するため無限ループ
-        loop {
-            thread::sleep(std::time::Duration::from_secs(1));
         }
+
+        log::info!("[AudioManager] Command channel closed, stopping audio thread");
+        Ok(())
     }
-    
-    fn build_stream<T>(
-        device: &cpal::Device,
-        config: &StreamConfig,
-        buffer: Arc<Mutex<VecDeque<f32>>>,
-        volume: Arc<Mutex<f32>>,
-        is_playing: Arc<Mutex<bool>>,
-        output_sample_rate: u32,
-        output_channels: u16,
-    ) -> Result<cpal::Stream>
-    where
-        T: cpal::Sample + cpal::SizedSample + cpal::FromSample<f32>,
-    {
-        use cpal::traits::DeviceTrait;
-        
-        let input_sample_rate = 44100; // Cartesia APIの出力
-        // 注意: これは入力サンプルの進み方を表す（出力1サンプルに対して入力を何サンプル進めるか）
-        let resample_ratio = input_sample_rate as f32 / output_sample_rate as f32;
-        let mut resample_position = 0.0;
-        
-        log::info!("[AudioManager] Building stream - Input: {} Hz, Output: {} Hz, Input step per output sample: {:.3}", 
-                   input_sample_rate, output_sample_rate, resample_ratio);
-        
-        let err_fn = |err: cpal::StreamError| log::error!("[AudioManager] Stream error: {}", err);
-        
-        let stream = device.build_output_stream(
-            config,
-            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
-                let is_playing = is_playing.lock().map(|p| *p).unwrap_or(false);
-                if !is_playing {
-                    for sample in data.iter_mut() {
-                        *sample = T::EQUILIBRIUM;
-                    }
-                    return;
-                }
-                
-                let volume = volume.lock().map(|v| *v).unwrap_or(1.0);
-                let mut buffer = match buffer.lock() {
-                    Ok(b) => b,
-                    Err(_) => return,
-                };
-                
-                for frame in data.chunks_mut(output_channels as usize) {
-                    // リサンプリング位置の整数部分と小数部分
-                    let sample_index = resample_position as usize;
-                    let fraction = resample_position - sample_index as f32;
-                    
-                    // 線形補間によるリサンプリング
-                    let interpolated_sample = if sample_index < buffer.len() {
-                        let sample1 = buffer.get(sample_index).copied().unwrap_or(0.0);
-                        let sample2 = buffer.get(sample_index + 1).copied().unwrap_or(sample1);
-                        // 線形補間: sample1 * (1 - fraction) + sample2 * fraction
-                        sample1 * (1.0 - fraction) + sample2 * fraction
-                    } else {
-                        0.0
-                    };
-                    
-                    // ボリューム適用
-                    let sample_with_volume = interpolated_sample * volume;
-                    
-                    // 全チャンネルに同じサンプルを出力（モノラル→ステレオ/マルチチャンネル）
-                    for channel_sample in frame.iter_mut() {
-                        *channel_sample = T::from_sample(sample_with_volume);
-                    }
-                    
-                    // リサンプリング位置を進める
-                    resample_position += resample_ratio;
-                    
-                    // 処理済みのサンプルをバッファから削除
-                    let samples_to_remove = resample_position as usize;
-                    if samples_to_remove > 0 {
-                        let buffer_len = buffer.len();
-                        buffer.drain(..samples_to_remove.min(buffer_len));
-                        resample_position -= samples_to_remove as f32;
-                    }
-                }
+
+    /// ストリームエラーや既定デバイスの変更を検知したら出力先を開き直す
+    /// 開き直している間は再生バッファを消費せずに保持する
+    fn check_sink(
+        config: &SinkConfig,
+        sink: &mut Option<Box<dyn AudioSink>>,
+        lost: &mut Option<(Instant, RebuildReason)>,
+        playback: &Arc<Mutex<PlaybackState>>,
+        events: &broadcast::Sender<AudioEvent>,
+    ) {
+        let reason = match (sink.as_ref(), *lost) {
+            (Some(current), _) => match current.rebuild_reason() {
+                Some(reason) => reason,
+                None => return,
             },
-            err_fn,
-            None,
-        )?;
-        
-        Ok(stream)
-    }
-    
-    pub fn play_audio(&self, audio_data: Vec<f32>) -> Result<()> {
-        self.command_sender.send(AudioCommand::Play(audio_data))
+            (None, Some((_, reason))) => reason,
+            (None, None) => return,
+        };
+
+        // 古いストリームを閉じてから開き直す
+        *sink = None;
+        match open_sink_strict(config, playback.clone()) {
+            Ok(new_sink) => {
+                Self::notify_device_changed(events, new_sink.as_ref(), reason);
+                *sink = Some(new_sink);
+                *lost = None;
+            }
+            Err(e) => {
+                let since = lost.map(|(since, _)| since).unwrap_or_else(Instant::now);
+                if since.elapsed() >= DEVICE_LOST_GRACE {
+                    // デバイスが戻らなければヌルシンクで再生を進め、デバイスが使えるようになるのを待つ
+                    let fallback = open_device_fallback(playback.clone());
+                    Self::notify_device_changed(events, fallback.as_ref(), RebuildReason::DeviceLost);
+                    *sink = Some(fallback);
+                    *lost = None;
+                } else {
+                    log::warn!("[AudioManager] Failed to reopen output ({}): {}", reason.as_str(), e);
+                    *lost = Some((since, reason));
+                }
+            }
+        }
+    }
+
+    fn notify_device_changed(
+        events: &broadcast::Sender<AudioEvent>,
+        sink: &dyn AudioSink,
+        reason: RebuildReason,
+    ) {
+        log::info!("[AudioManager] Output changed ({}): {}", reason.as_str(), sink.description());
+        let _ = events.send(AudioEvent::DeviceChanged {
+            device: sink.description(),
+            reason: reason.as_str().to_string(),
+        });
+    }
+
+    /// 発話を再生するレーンを指定する
+    pub fn open_utterance(&self, utterance_id: &str, lane: &str) -> Result<()> {
+        self.command_sender
+            .send(AudioCommand::Open { utterance_id: utterance_id.to_string(), lane: lane.to_string() })
+            .map_err(|e| anyhow::anyhow!("Failed to send open command: {}", e))
+    }
+
+    pub fn play_audio(&self, utterance_id: &str, samples: Vec<f32>) -> Result<()> {
+        self.command_sender
+            .send(AudioCommand::Play { utterance_id: utterance_id.to_string(), samples })
             .map_err(|e| anyhow::anyhow!("Failed to send play command: {}", e))
     }
-    
+
+    /// 発話の最後のチャンクを送信済みであることを通知する
+    pub fn finish_utterance(&self, utterance_id: &str) -> Result<()> {
+        self.command_sender
+            .send(AudioCommand::Finish { utterance_id: utterance_id.to_string() })
+            .map_err(|e| anyhow::anyhow!("Failed to send finish command: {}", e))
+    }
+
     pub fn stop(&self) -> Result<()> {
         self.command_sender.send(AudioCommand::Stop)
             .map_err(|e| anyhow::anyhow!("Failed to send stop command: {}", e))
     }
-    
+
+    /// 単語の切れ目まで再生してから停止する
+    pub fn soft_stop(&self) -> Result<()> {
+        self.command_sender.send(AudioCommand::SoftStop)
+            .map_err(|e| anyhow::anyhow!("Failed to send soft stop command: {}", e))
+    }
+
+    /// レーンで再生中の発話だけを停止して次の発話へ進む
+    pub fn skip(&self, lane: &str) -> Result<()> {
+        self.command_sender.send(AudioCommand::Skip { lane: lane.to_string() })
+            .map_err(|e| anyhow::anyhow!("Failed to send skip command: {}", e))
+    }
+
+    /// 指定した発話だけをフェードアウトして停止する
+    pub fn stop_utterance(&self, utterance_id: &str) -> Result<()> {
+        self.command_sender
+            .send(AudioCommand::StopUtterance { utterance_id: utterance_id.to_string() })
+            .map_err(|e| anyhow::anyhow!("Failed to send stop command: {}", e))
+    }
+
+    /// 発話をフェードアウトして一時退避する（resumeで続きから再生）
+    pub fn suspend(&self, utterance_id: &str) -> Result<()> {
+        self.command_sender
+            .send(AudioCommand::Suspend { utterance_id: utterance_id.to_string() })
+            .map_err(|e| anyhow::anyhow!("Failed to send suspend command: {}", e))
+    }
+
+    pub fn resume(&self, utterance_id: &str) -> Result<()> {
+        self.command_sender
+            .send(AudioCommand::Resume { utterance_id: utterance_id.to_string() })
+            .map_err(|e| anyhow::anyhow!("Failed to send resume command: {}", e))
+    }
+
+    pub fn set_ducking(&self, ducking: DuckingConfig) -> Result<()> {
+        self.command_sender.send(AudioCommand::SetDucking(ducking))
+            .map_err(|e| anyhow::anyhow!("Failed to send ducking command: {}", e))
+    }
+
+    pub fn set_fade(&self, fade: FadeConfig) -> Result<()> {
+        self.command_sender.send(AudioCommand::SetFade(fade))
+            .map_err(|e| anyhow::anyhow!("Failed to send fade command: {}", e))
+    }
+
+    pub fn set_jitter(&self, jitter: JitterConfig) -> Result<()> {
+        self.command_sender.send(AudioCommand::SetJitter(jitter))
+            .map_err(|e| anyhow::anyhow!("Failed to send jitter command: {}", e))
+    }
+
+    /// 再生バッファの統計（アンダーラン回数など）
+    pub fn stats(&self) -> PlaybackStats {
+        self.playback.lock().map(|state| state.stats()).unwrap_or_default()
+    }
+
+    pub fn set_meter(&self, meter: MeterConfig) -> Result<()> {
+        self.command_sender.send(AudioCommand::SetMeter(meter))
+            .map_err(|e| anyhow::anyhow!("Failed to send meter command: {}", e))
+    }
+
+    /// 出力先を切り替える
+    pub fn set_sink(&self, sink: SinkConfig) -> Result<()> {
+        self.command_sender.send(AudioCommand::SetSink(sink))
+            .map_err(|e| anyhow::anyhow!("Failed to send sink command: {}", e))
+    }
+
     pub fn set_volume(&self, volume: f32) -> Result<()> {
         self.command_sender.send(AudioCommand::SetVolume(volume))
             .map_err(|e| anyhow::anyhow!("Failed to send volume command: {}", e))
     }
-}
\ No newline at end of file
+
+    pub fn set_speed(&self, speed: f32) -> Result<()> {
+        self.command_sender.send(AudioCommand::SetSpeed(speed))
+            .map_err(|e| anyhow::anyhow!("Failed to send speed command: {}", e))
+    }
+
+    /// フェードアウトして一時停止する・続きから再生する
+    pub fn set_paused(&self, paused: bool) -> Result<()> {
+        self.command_sender.send(AudioCommand::SetPaused(paused))
+            .map_err(|e| anyhow::anyhow!("Failed to send pause command: {}", e))
+    }
+
+    /// レーンの音量と速度の倍率を設定する（レーンが無ければ追加する）
+    pub fn set_lane(&self, lane: &str, volume: f32, speed: f32) -> Result<()> {
+        self.command_sender
+            .send(AudioCommand::SetLane { lane: lane.to_string(), volume, speed })
+            .map_err(|e| anyhow::anyhow!("Failed to send lane command: {}", e))
+    }
+}
@@ -0,0 +1,196 @@
+/**
+ * 出力レベルの計測
+ * 実際に出力したサンプルから20msごとのRMS・ピークと口の開き具合を求める
+ */
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use super::playback::INPUT_SAMPLE_RATE;
+
+/// レベルを計測するブロックの長さ（ミリ秒）
+pub const LEVEL_BLOCK_MS: u32 = 20;
+
+/// レベルメーターの設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeterConfig {
+    pub enabled: bool,
+    /// `audio-level` イベントを送る最短間隔（ミリ秒）
+    pub interval_ms: u32,
+    /// 口が閉じているとみなすレベル（dBFS）
+    pub silence_db: f32,
+    /// 口が全開になるレベル（dBFS）
+    pub full_open_db: f32,
+    /// 口を閉じるときの時定数（ミリ秒）
+    pub release_ms: u32,
+}
+
+impl Default for MeterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_ms: 50,
+            silence_db: -50.0,
+            full_open_db: -15.0,
+            release_ms: 120,
+        }
+    }
+}
+
+/// 1ブロック分の出力レベル
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioLevel {
+    pub rms: f32,
+    pub peak: f32,
+    pub rms_db: f32,
+    pub peak_db: f32,
+    /// 口の開き具合（0〜1）
+    pub mouth_open: f32,
+}
+
+fn to_db(value: f32) -> f32 {
+    if value > 0.0 {
+        (20.0 * value.log10()).max(-100.0)
+    } else {
+        -100.0
+    }
+}
+
+/// 出力サンプルを受け取り、ブロックごとにレベルを返す
+pub struct LevelMeter {
+    config: MeterConfig,
+    /// 現在のブロックに含まれる入力サンプル相当の長さ
+    elapsed: f32,
+    sum_squares: f64,
+    count: usize,
+    peak: f32,
+    mouth_open: f32,
+    previous_silent: bool,
+}
+
+impl LevelMeter {
+    pub fn new(config: MeterConfig) -> Self {
+        Self {
+            config,
+            elapsed: 0.0,
+            sum_squares: 0.0,
+            count: 0,
+            peak: 0.0,
+            mouth_open: 0.0,
+            previous_silent: true,
+        }
+    }
+
+    pub fn set_config(&mut self, config: MeterConfig) {
+        self.config = config;
+    }
+
+    /// 出力した1サンプルを加える
+    /// `step` は出力1サンプルが入力サンプル何個分に相当するか
+    pub fn add(&mut self, sample: f32, step: f32) -> Option<AudioLevel> {
+        if !self.config.enabled {
+            return None;
+        }
+        self.sum_squares += (sample * sample) as f64;
+        self.count += 1;
+        self.peak = self.peak.max(sample.abs());
+        self.elapsed += step;
+
+        let block_length = (LEVEL_BLOCK_MS * INPUT_SAMPLE_RATE) as f32 / 1000.0;
+        if self.elapsed < block_length {
+            return None;
+        }
+
+        let rms = (self.sum_squares / self.count as f64).sqrt() as f32;
+        let peak = self.peak;
+        self.elapsed -= block_length;
+        self.sum_squares = 0.0;
+        self.count = 0;
+        self.peak = 0.0;
+
+        // 口の開き具合はすぐに開き、ゆっくり閉じる
+        let range = (self.config.full_open_db - self.config.silence_db).max(1.0);
+        let target = ((to_db(rms) - self.config.silence_db) / range).clamp(0.0, 1.0);
+        if target >= self.mouth_open {
+            self.mouth_open = target;
+        } else {
+            let release = (-(LEVEL_BLOCK_MS as f32) / self.config.release_ms.max(1) as f32).exp();
+            self.mouth_open = target + (self.mouth_open - target) * release;
+            if self.mouth_open < 0.01 {
+                self.mouth_open = 0.0;
+            }
+        }
+
+        // 無音が続く間は通知しない
+        let silent = peak == 0.0 && self.mouth_open == 0.0;
+        if silent && self.previous_silent {
+            return None;
+        }
+        self.previous_silent = silent;
+
+        Some(AudioLevel {
+            rms,
+            peak,
+            rms_db: to_db(rms),
+            peak_db: to_db(peak),
+            mouth_open: self.mouth_open,
+        })
+    }
+}
+
+/// レベル通知の間引き（間引いた区間のピークは保持する）
+pub struct LevelThrottle {
+    last_sent: Option<Instant>,
+    pending_peak: f32,
+}
+
+impl Default for LevelThrottle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LevelThrottle {
+    pub fn new() -> Self {
+        Self { last_sent: None, pending_peak: 0.0 }
+    }
+
+    /// 送るべきレベルを返す（無音になったときは間隔に関係なく送る）
+    pub fn offer(&mut self, mut level: AudioLevel, interval: Duration) -> Option<AudioLevel> {
+        self.pending_peak = self.pending_peak.max(level.peak);
+        let now = Instant::now();
+        let due = self.last_sent.is_none_or(|last| now.duration_since(last) >= interval);
+        if !due && level.peak > 0.0 {
+            return None;
+        }
+        level.peak = self.pending_peak;
+        level.peak_db = to_db(level.peak);
+        self.pending_peak = 0.0;
+        self.last_sent = Some(now);
+        Some(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_per_block() {
+        let mut meter = LevelMeter::new(MeterConfig::default());
+        let block = (LEVEL_BLOCK_MS * INPUT_SAMPLE_RATE / 1000) as usize;
+
+        let levels: Vec<AudioLevel> = (0..block * 2).filter_map(|_| meter.add(0.5, 1.0)).collect();
+        assert_eq!(levels.len(), 2);
+        assert!((levels[0].rms - 0.5).abs() < 1e-3);
+        assert!((levels[0].peak - 0.5).abs() < 1e-6);
+        assert_eq!(levels[0].mouth_open, 1.0);
+
+        // 無音になると口は徐々に閉じ、閉じきった後は通知しない
+        let silent: Vec<AudioLevel> = (0..block * 50).filter_map(|_| meter.add(0.0, 1.0)).collect();
+        assert!(silent.windows(2).all(|w| w[1].mouth_open <= w[0].mouth_open));
+        assert_eq!(silent.last().unwrap().mouth_open, 0.0);
+        assert!(silent.len() < 50);
+    }
+}
@@ -4,4 +4,17 @@
  * OBSのアプリケーション音声キャプチャで音を取得可能にする
  */
 
-pub mod manager;
\ No newline at end of file
+pub mod biquad;
+pub mod config;
+pub mod effects;
+pub mod export;
+pub mod jitter;
+pub mod lane;
+pub mod loudness;
+pub mod manager;
+pub mod meter;
+pub mod pipeline;
+pub mod playback;
+pub mod sink;
+pub mod stretch;
+pub mod trim;
\ No newline at end of file
@@ -0,0 +1,101 @@
+/**
+ * 発話単位の音声処理パイプライン
+ * デコード済みの音声の前後の無音を削り、エフェクトとラウドネス正規化を適用してからAudioManagerへ渡す
+ */
+
+use super::config::AudioConfig;
+use super::effects::{EffectChain, EffectConfig};
+use super::loudness::LoudnessNormalizer;
+use super::playback::INPUT_SAMPLE_RATE;
+use super::trim::SilenceTrimmer;
+
+/// PCM f32le形式のバイトデータをf32配列に変換
+pub fn decode_pcm_f32le(audio_data: &[u8]) -> Vec<f32> {
+    audio_data
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+pub struct UtterancePipeline {
+    trimmer: Option<SilenceTrimmer>,
+    effects: EffectChain,
+    loudness: Option<LoudnessNormalizer>,
+    /// 最後に掛ける音量の倍率
+    gain: f32,
+    gap_samples: usize,
+}
+
+impl UtterancePipeline {
+    pub fn new(effects: &[EffectConfig], config: &AudioConfig) -> Self {
+        Self {
+            trimmer: config
+                .trim
+                .enabled
+                .then(|| SilenceTrimmer::new(&config.trim, INPUT_SAMPLE_RATE)),
+            gap_samples: if config.trim.enabled {
+                (config.trim.inter_utterance_gap_ms as u64 * INPUT_SAMPLE_RATE as u64 / 1000) as usize
+            } else {
+                0
+            },
+            effects: EffectChain::new(effects, INPUT_SAMPLE_RATE),
+            loudness: config
+                .loudness
+                .enabled
+                .then(|| LoudnessNormalizer::new(&config.loudness, INPUT_SAMPLE_RATE)),
+            gain: 1.0,
+        }
+    }
+
+    /// 読み上げごとの音量を設定する（ラウドネス正規化の後に掛ける）
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    fn apply_gain(&self, mut samples: Vec<f32>) -> Vec<f32> {
+        if self.gain != 1.0 {
+            samples.iter_mut().for_each(|sample| *sample *= self.gain);
+        }
+        samples
+    }
+
+    /// 受信したチャンクを処理する
+    pub fn process(&mut self, mut samples: Vec<f32>) -> Vec<f32> {
+        if let Some(trimmer) = self.trimmer.as_mut() {
+            samples = trimmer.process(&samples);
+        }
+        self.effects.process(&mut samples);
+        let samples = match self.loudness.as_mut() {
+            Some(loudness) => loudness.process(&samples),
+            None => samples,
+        };
+        self.apply_gain(samples)
+    }
+
+    /// 発話の終端で残りの出力（エフェクトの余韻や先読み分）を取り出す
+    pub fn finish(&mut self) -> Vec<f32> {
+        let mut tail = match self.trimmer.as_mut() {
+            Some(trimmer) => trimmer.finish(),
+            None => Vec::new(),
+        };
+        if !self.effects.is_empty() {
+            self.effects.process(&mut tail);
+            tail.extend(self.effects.flush());
+        }
+        let output = match self.loudness.as_mut() {
+            Some(loudness) => {
+                let mut output = loudness.process(&tail);
+                output.extend(loudness.finish());
+                output
+            }
+            None => tail,
+        };
+        self.apply_gain(output)
+    }
+
+    /// 次の発話との間に入れる無音（無音除去が無効なら空）
+    pub fn inter_utterance_gap(&self) -> Vec<f32> {
+        vec![0.0; self.gap_samples]
+    }
+}
@@ -0,0 +1,523 @@
+/**
+ * 再生バッファと発話ごとの再生位置管理
+ * 出力デバイスが実際に消費したサンプル数をもとに再生イベントを発行する
+ */
+
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use super::config::{DuckingConfig, FadeConfig};
+use super::jitter::{JitterBuffer, JitterConfig, PlaybackStats};
+use super::lane::{ms_to_samples, Lane, Shared};
+use super::meter::{AudioLevel, LevelMeter, MeterConfig};
+
+/// Cartesia APIの出力サンプルレート（モノラル, f32le）
+pub const INPUT_SAMPLE_RATE: u32 = 44100;
+
+/// レーンを指定せずに登録した発話を再生するレーン
+pub const MAIN_LANE: &str = "main";
+/// 割り込みの読み上げを重ねて再生するレーン（再生中はほかのレーンの音量を下げる）
+pub const OVERLAY_LANE: &str = "overlay";
+
+/// 再生状態の変化を通知するイベント
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum AudioEvent {
+    Started {
+        utterance_id: String,
+    },
+    Progress {
+        utterance_id: String,
+        position_ms: u64,
+        duration_ms: u64,
+    },
+    Finished {
+        utterance_id: String,
+        cancelled: bool,
+    },
+    /// 割り込みのため一時退避した（再開すると再びStartedが通知される）
+    Suspended {
+        utterance_id: String,
+    },
+    /// 出力先を開き直した（デバイスの抜き差し・既定デバイスの変更など）
+    DeviceChanged {
+        device: String,
+        reason: String,
+    },
+    /// 出力レベル（20msごと）
+    Level(AudioLevel),
+    /// 再生中にデータが尽きた（バッファが溜まるまで一時的に無音になる）
+    Underrun {
+        utterance_id: String,
+        underruns: u64,
+        target_ms: u64,
+    },
+}
+
+impl AudioEvent {
+    /// Tauriイベント名
+    pub fn name(&self) -> &'static str {
+        match self {
+            AudioEvent::Started { .. } => "playback-started",
+            AudioEvent::Progress { .. } => "playback-progress",
+            AudioEvent::Finished { .. } => "playback-finished",
+            AudioEvent::Suspended { .. } => "playback-suspended",
+            AudioEvent::DeviceChanged { .. } => "audio-device-changed",
+            AudioEvent::Level(_) => "audio-level",
+            AudioEvent::Underrun { .. } => "audio-underrun",
+        }
+    }
+}
+
+/// 停止の対象
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopTarget {
+    /// 全ての発話
+    All,
+    /// 指定したレーンで再生中の発話のみ（スキップ）
+    Current(String),
+    /// 指定した発話
+    Utterance(String),
+}
+
+pub struct PlaybackState {
+    /// 先頭が通常レーン（読み上げキューのレーンは発話の登録時に追加する）
+    lanes: Vec<Lane>,
+    shared: Shared,
+    volume: f32,
+    ducking: DuckingConfig,
+    /// ダッキングで下げられているレーンに掛けるゲイン
+    duck_gain: f32,
+    /// 一時停止中か（フェードアウトし終えたら再生位置を進めない）
+    paused: bool,
+    /// 一時停止・再開のフェードのゲイン
+    pause_gain: f32,
+    meter: LevelMeter,
+}
+
+impl PlaybackState {
+    pub fn new(events: broadcast::Sender<AudioEvent>) -> Self {
+        Self {
+            lanes: vec![Lane::new(MAIN_LANE, false), Lane::new(OVERLAY_LANE, true)],
+            shared: Shared::new(FadeConfig::default(), JitterBuffer::new(JitterConfig::default()), events),
+            volume: 1.0,
+            ducking: DuckingConfig::default(),
+            duck_gain: 1.0,
+            paused: false,
+            pause_gain: 1.0,
+            meter: LevelMeter::new(MeterConfig::default()),
+        }
+    }
+
+    /// 発話が登録されているレーン
+    fn lane_index(&self, id: &str) -> Option<usize> {
+        self.lanes.iter().position(|lane| lane.contains(id))
+    }
+
+    /// 発話のレーンを取得する（未登録のIDなら通常レーンに新規作成, 停止済みのIDならNone）
+    fn lane_index_or_insert(&mut self, id: &str) -> Option<usize> {
+        if self.shared.is_cancelled(id) {
+            return None;
+        }
+        self.lane_index(id).or_else(|| {
+            self.lanes[0].insert(id);
+            Some(0)
+        })
+    }
+
+    /// 名前でレーンを取得する（無ければ追加する）
+    fn lane_mut(&mut self, name: &str) -> &mut Lane {
+        let index = match self.lanes.iter().position(|lane| lane.name == name) {
+            Some(index) => index,
+            None => {
+                log::info!("[AudioManager] Added {} lane", name);
+                self.lanes.push(Lane::new(name, false));
+                self.lanes.len() - 1
+            }
+        };
+        &mut self.lanes[index]
+    }
+
+    /// 再生するレーンを指定して発話を登録する（登録済み・停止済みなら何もしない）
+    pub fn open(&mut self, id: &str, lane: &str) {
+        if self.shared.is_cancelled(id) || self.lane_index(id).is_some() {
+            return;
+        }
+        self.lane_mut(lane).insert(id);
+    }
+
+    /// レーンの音量を設定する（速度の倍率は音声を渡す前に反映する）
+    pub fn set_lane(&mut self, name: &str, volume: f32) {
+        self.lane_mut(name).volume = volume.clamp(0.0, 1.0);
+    }
+
+    /// 発話に音声データを追加する
+    pub fn push(&mut self, id: &str, samples: Vec<f32>) {
+        if let Some(index) = self.lane_index_or_insert(id) {
+            self.lanes[index].push(id, samples, &mut self.shared);
+        }
+    }
+
+    /// 発話の全データを受信済みとしてマークする
+    pub fn finish(&mut self, id: &str) {
+        if let Some(index) = self.lane_index_or_insert(id) {
+            self.lanes[index].finish(id);
+        }
+    }
+
+    pub fn set_fade(&mut self, fade: FadeConfig) {
+        self.shared.fade = fade;
+    }
+
+    pub fn set_ducking(&mut self, ducking: DuckingConfig) {
+        self.ducking = ducking;
+    }
+
+    pub fn set_meter(&mut self, meter: MeterConfig) {
+        self.meter.set_config(meter);
+    }
+
+    pub fn set_jitter(&mut self, jitter: JitterConfig) {
+        self.shared.jitter.set_config(jitter);
+    }
+
+    pub fn stats(&self) -> PlaybackStats {
+        self.shared.jitter.stats()
+    }
+
+    /// 発話が登録されているか（再生待ち・再生中・一時退避中）
+    pub fn contains(&self, id: &str) -> bool {
+        self.lane_index(id).is_some()
+    }
+
+    /// 再生できる音声データが残っていないか
+    pub fn is_idle(&self) -> bool {
+        self.lanes.iter().all(|lane| lane.is_idle())
+    }
+
+    /// フェードアウトしてから停止する（スキップは指定したレーンのみ）
+    pub fn stop(&mut self, target: StopTarget) {
+        for lane in self.lanes.iter_mut().filter(|lane| is_targeted(lane, &target)) {
+            lane.stop(target.clone(), &mut self.shared);
+        }
+    }
+
+    /// 単語の切れ目（短い無音）まで再生してからフェードアウトする
+    /// 割り込みを重ねるレーンは切れ目を待たずに直ちにフェードアウトする
+    pub fn soft_stop(&mut self, target: StopTarget) {
+        for lane in self.lanes.iter_mut().filter(|lane| is_targeted(lane, &target)) {
+            if lane.ducks_others {
+                lane.stop(target.clone(), &mut self.shared);
+            } else {
+                lane.soft_stop(target.clone(), &mut self.shared);
+            }
+        }
+    }
+
+    /// 発話をフェードアウトして一時退避する
+    pub fn suspend(&mut self, id: &str) {
+        for lane in self.lanes.iter_mut() {
+            if lane.suspend(id, &mut self.shared) {
+                return;
+            }
+        }
+    }
+
+    /// 一時退避した発話を続きから再生する
+    /// 既に停止・再生し終えていた場合は停止済みとして完了を通知する
+    pub fn resume(&mut self, id: &str) {
+        for lane in self.lanes.iter_mut() {
+            if lane.resume(id) {
+                return;
+            }
+        }
+        if self.lane_index(id).is_none() {
+            self.shared.emit(AudioEvent::Finished {
+                utterance_id: id.to_string(),
+                cancelled: true,
+            });
+        }
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    /// 全レーンをフェードアウトして一時停止する・続きから再生する
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// 一時停止・再開のフェードを進める（再生位置を進めるか）
+    fn advance_pause(&mut self, step: f32) -> bool {
+        let ms = if self.paused { self.shared.fade.fade_out_ms } else { self.shared.fade.fade_in_ms };
+        let length = ms_to_samples(ms);
+        let rate = if length < 1.0 { 1.0 } else { step / length };
+        self.pause_gain = if self.paused {
+            (self.pause_gain - rate).max(0.0)
+        } else {
+            (self.pause_gain + rate).min(1.0)
+        };
+        !self.paused || self.pause_gain > 0.0
+    }
+
+    /// 出力バッファを埋める
+    /// `step` は出力1サンプルあたりに進める入力サンプル数
+    pub fn render(&mut self, data: &mut [f32], channels: usize, step: f32) {
+        for frame in data.chunks_mut(channels) {
+            let sample = if self.advance_pause(step) {
+                self.next_sample(step) * self.volume * self.pause_gain
+            } else {
+                0.0
+            };
+            if let Some(level) = self.meter.add(sample, step) {
+                self.shared.emit(AudioEvent::Level(level));
+            }
+            // 全チャンネルに同じサンプルを出力（モノラル→ステレオ/マルチチャンネル）
+            for channel_sample in frame.iter_mut() {
+                *channel_sample = sample;
+            }
+        }
+    }
+
+    /// ダッキングのゲインを目標に向けて進める
+    fn advance_ducking(&mut self, step: f32) {
+        let floor = 10f32.powf(self.ducking.duck_db / 20.0);
+        let active = self.lanes.iter().any(|lane| lane.ducks_others && lane.is_playing());
+        let target = if active { floor } else { 1.0 };
+        let ramp_ms = if target < self.duck_gain { self.ducking.attack_ms } else { self.ducking.release_ms };
+        let ramp = ms_to_samples(ramp_ms);
+        let rate = if ramp < 1.0 { 1.0 } else { (1.0 - floor) * step / ramp };
+        self.duck_gain = if target < self.duck_gain {
+            (self.duck_gain - rate).max(target)
+        } else {
+            (self.duck_gain + rate).min(target)
+        };
+    }
+
+    /// 全レーンの次のサンプルを混ぜる
+    fn next_sample(&mut self, step: f32) -> f32 {
+        self.advance_ducking(step);
+        let mut mixed = 0.0;
+        for lane in self.lanes.iter_mut() {
+            let sample = lane.next_sample(step, &mut self.shared) * lane.volume;
+            mixed += if lane.ducks_others { sample } else { sample * self.duck_gain };
+        }
+        mixed.clamp(-1.0, 1.0)
+    }
+}
+
+/// 停止の対象に含まれるレーンか
+fn is_targeted(lane: &Lane, target: &StopTarget) -> bool {
+    match target {
+        StopTarget::Current(name) => &lane.name == name,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(rx: &mut broadcast::Receiver<AudioEvent>) -> Vec<AudioEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn test_finished_only_after_samples_consumed() {
+        let (tx, mut rx) = broadcast::channel(64);
+        let mut state = PlaybackState::new(tx);
+        state.push("a", vec![0.5; 100]);
+        state.finish("a");
+
+        let mut out = vec![0.0; 50];
+        state.render(&mut out, 1, 1.0);
+        let events = collect(&mut rx);
+        assert!(matches!(events[0], AudioEvent::Started { .. }));
+        assert!(!events.iter().any(|e| matches!(e, AudioEvent::Finished { .. })));
+
+        let mut out = vec![0.0; 60];
+        state.render(&mut out, 1, 1.0);
+        let events = collect(&mut rx);
+        assert!(matches!(
+            events.last(),
+            Some(AudioEvent::Finished { cancelled: false, .. })
+        ));
+    }
+
+    #[test]
+    fn test_stop_cancels_pending_utterances() {
+        let (tx, mut rx) = broadcast::channel(64);
+        let mut state = PlaybackState::new(tx);
+        state.push("a", vec![0.5; 10]);
+        state.push("b", vec![0.5; 10]);
+        state.stop(StopTarget::All);
+
+        let events = collect(&mut rx);
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .all(|e| matches!(e, AudioEvent::Finished { cancelled: true, .. })));
+
+        // 停止後に届いたチャンクは破棄される
+        state.push("a", vec![0.5; 10]);
+        let mut out = vec![0.0; 10];
+        state.render(&mut out, 1, 1.0);
+        assert!(out.iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_skip_fades_out_current_utterance() {
+        let (tx, mut rx) = broadcast::channel(64);
+        let mut state = PlaybackState::new(tx);
+        state.set_fade(FadeConfig { fade_in_ms: 0, fade_out_ms: 1, ..FadeConfig::default() });
+        state.set_jitter(JitterConfig { enabled: false, ..JitterConfig::default() });
+        state.push("a", vec![0.5; 4410]);
+        state.push("b", vec![0.5; 100]);
+        state.finish("b");
+
+        let mut out = vec![0.0; 10];
+        state.render(&mut out, 1, 1.0);
+        state.stop(StopTarget::Current(MAIN_LANE.to_string()));
+
+        // 1ms（約44サンプル）でフェードアウトし、次の発話が始まる
+        let mut out = vec![0.0; 44];
+        state.render(&mut out, 1, 1.0);
+        assert!(out.windows(2).all(|w| w[1] <= w[0]));
+        let mut out = vec![0.0; 20];
+        state.render(&mut out, 1, 1.0);
+
+        let events = collect(&mut rx);
+        assert!(events.iter().any(|e| matches!(
+            e,
+            AudioEvent::Finished { utterance_id, cancelled: true } if utterance_id == "a"
+        )));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            AudioEvent::Started { utterance_id } if utterance_id == "b"
+        )));
+    }
+
+    #[test]
+    fn test_suspend_and_resume_around_overlay() {
+        let (tx, mut rx) = broadcast::channel(256);
+        let mut state = PlaybackState::new(tx);
+        state.set_fade(FadeConfig { fade_in_ms: 0, fade_out_ms: 1, ..FadeConfig::default() });
+        state.set_jitter(JitterConfig { enabled: false, ..JitterConfig::default() });
+        state.push("a", vec![0.5; 1000]);
+        state.finish("a");
+
+        let mut out = vec![0.0; 100];
+        state.render(&mut out, 1, 1.0);
+        state.suspend("a");
+        let mut out = vec![0.0; 100];
+        state.render(&mut out, 1, 1.0);
+        assert!(out[60..].iter().all(|s| *s == 0.0));
+
+        // 割り込みは重ねるレーンで再生され、一時退避した発話はその後に続きから再生される
+        state.open("b", OVERLAY_LANE);
+        state.push("b", vec![0.25; 100]);
+        state.finish("b");
+        let mut out = vec![0.0; 120];
+        state.render(&mut out, 1, 1.0);
+        state.resume("a");
+        let mut out = vec![0.0; 2000];
+        state.render(&mut out, 1, 1.0);
+        // フェードアウトで消えた分を除き、中断した位置から最後まで再生される
+        let played = out.iter().filter(|s| **s != 0.0).count();
+        assert!(played > 800 && played < 900);
+
+        let events = collect(&mut rx);
+        assert!(events.iter().any(|e| matches!(e, AudioEvent::Suspended { utterance_id } if utterance_id == "a")));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            AudioEvent::Finished { utterance_id, cancelled: false } if utterance_id == "a"
+        )));
+    }
+
+    #[test]
+    fn test_lanes_mix_with_own_volume() {
+        let (tx, mut rx) = broadcast::channel(64);
+        let mut state = PlaybackState::new(tx);
+        state.set_fade(FadeConfig { fade_in_ms: 0, fade_out_ms: 0, ..FadeConfig::default() });
+        state.set_jitter(JitterConfig { enabled: false, ..JitterConfig::default() });
+        state.set_lane("alerts", 0.5);
+        state.open("a", "chat");
+        state.push("a", vec![0.2; 1000]);
+        state.finish("a");
+        state.open("b", "alerts");
+        state.push("b", vec![0.4; 1000]);
+        state.finish("b");
+
+        // 別々のレーンの発話は重ねて再生される
+        let mut out = vec![0.0; 10];
+        state.render(&mut out, 1, 1.0);
+        assert!((out[5] - 0.4).abs() < 1e-6);
+
+        // スキップは指定したレーンの発話だけを止める
+        state.stop(StopTarget::Current("chat".to_string()));
+        state.render(&mut out, 1, 1.0);
+        assert!((out[5] - 0.2).abs() < 1e-6);
+        let events = collect(&mut rx);
+        assert!(events.iter().any(|e| matches!(
+            e,
+            AudioEvent::Finished { utterance_id, cancelled: true } if utterance_id == "a"
+        )));
+        assert!(!events.iter().any(|e| matches!(e, AudioEvent::Finished { utterance_id, .. } if utterance_id == "b")));
+    }
+
+    #[test]
+    fn test_pause_keeps_position() {
+        let (tx, mut rx) = broadcast::channel(64);
+        let mut state = PlaybackState::new(tx);
+        state.set_fade(FadeConfig { fade_in_ms: 0, fade_out_ms: 0, ..FadeConfig::default() });
+        state.set_jitter(JitterConfig { enabled: false, ..JitterConfig::default() });
+        state.push("a", vec![0.5; 100]);
+        state.finish("a");
+
+        let mut out = vec![0.0; 50];
+        state.render(&mut out, 1, 1.0);
+        state.set_paused(true);
+        let mut paused = vec![0.0; 500];
+        state.render(&mut paused, 1, 1.0);
+        assert!(paused.iter().all(|s| *s == 0.0));
+
+        // 再開すると止めた位置から続きを再生する
+        state.set_paused(false);
+        let mut out = vec![0.0; 100];
+        state.render(&mut out, 1, 1.0);
+        assert_eq!(out.iter().filter(|s| **s != 0.0).count(), 50);
+        assert!(collect(&mut rx).iter().any(|e| matches!(e, AudioEvent::Finished { cancelled: false, .. })));
+    }
+
+    #[test]
+    fn test_prebuffer_and_underrun() {
+        let (tx, mut rx) = broadcast::channel(64);
+        let mut state = PlaybackState::new(tx);
+        state.set_fade(FadeConfig { fade_in_ms: 0, ..FadeConfig::default() });
+
+        // 目標量（100ms）に満たない間は再生を始めない
+        state.push("a", vec![0.5; 2000]);
+        let mut out = vec![0.0; 100];
+        state.render(&mut out, 1, 1.0);
+        assert!(out.iter().all(|s| *s == 0.0));
+        assert!(collect(&mut rx).is_empty());
+
+        state.push("a", vec![0.5; 4000]);
+        state.render(&mut out, 1, 1.0);
+        assert!(out.iter().any(|s| *s != 0.0));
+        assert!(matches!(collect(&mut rx)[0], AudioEvent::Started { .. }));
+
+        // 受信済みの分を再生し切るとアンダーランになる
+        let mut out = vec![0.0; 6000];
+        state.render(&mut out, 1, 1.0);
+        assert!(collect(&mut rx)
+            .iter()
+            .any(|e| matches!(e, AudioEvent::Underrun { underruns: 1, .. })));
+        assert_eq!(state.stats().underruns, 1);
+    }
+}
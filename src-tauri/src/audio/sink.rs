@@ -0,0 +1,486 @@
+/**
+ * 音声出力先（シンク）
+ * 再生バッファを消費する出力先を抽象化し、サウンドデバイスの無い環境でも再生処理を進められるようにする
+ */
+
+use anyhow::Result;
+use cpal::StreamConfig;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use super::playback::{PlaybackState, INPUT_SAMPLE_RATE};
+
+/// 実時間で消費するシンクの1ブロックの長さ（ミリ秒）
+const REALTIME_BLOCK_MS: u64 = 10;
+
+/// 既定の出力デバイスを問い合わせる間隔
+/// デバイスの列挙は重いため、切断はストリームのエラーで検知し、これは既定デバイスの切り替えを拾うための予備とする
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 出力先の設定
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SinkConfig {
+    /// 既定のサウンドデバイス
+    #[default]
+    Device,
+    /// 音を出さずに実時間で消費する
+    Null {
+        #[serde(default = "default_sample_rate")]
+        sample_rate: u32,
+    },
+    /// 再生した音声をWAVファイルに書き出す（無音区間は書き出さない）
+    WavFile {
+        path: String,
+        #[serde(default = "default_sample_rate")]
+        sample_rate: u32,
+    },
+}
+
+fn default_sample_rate() -> u32 {
+    48000
+}
+
+impl SinkConfig {
+    pub fn default_null() -> Self {
+        SinkConfig::Null { sample_rate: default_sample_rate() }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            SinkConfig::Device => Ok(()),
+            SinkConfig::Null { sample_rate } | SinkConfig::WavFile { sample_rate, .. }
+                if !(8000..=192000).contains(sample_rate) =>
+            {
+                Err("出力先のサンプルレートは8000〜192000 Hzの範囲で指定してください".to_string())
+            }
+            SinkConfig::WavFile { path, .. } if path.trim().is_empty() => {
+                Err("WAVファイルの出力先パスを指定してください".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// シンクを開き直す理由
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RebuildReason {
+    /// ストリームがエラーで停止した
+    StreamError,
+    /// 既定の出力デバイスが変わった
+    DefaultDeviceChanged,
+    /// 出力デバイスが再び使えるようになった
+    DeviceAvailable,
+    /// 出力デバイスが戻らないためヌルシンクに切り替えた
+    DeviceLost,
+    /// 設定で出力先が変更された
+    ConfigChanged,
+}
+
+impl RebuildReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RebuildReason::StreamError => "stream_error",
+            RebuildReason::DefaultDeviceChanged => "default_device_changed",
+            RebuildReason::DeviceAvailable => "device_available",
+            RebuildReason::DeviceLost => "device_lost",
+            RebuildReason::ConfigChanged => "config_changed",
+        }
+    }
+}
+
+/// 再生バッファを消費する出力先
+/// 破棄すると出力を停止する
+pub trait AudioSink {
+    fn description(&self) -> String;
+
+    /// 開き直す必要があれば理由を返す（定期的に呼ばれる）
+    fn rebuild_reason(&self) -> Option<RebuildReason> {
+        None
+    }
+}
+
+/// 設定に従ってシンクを開く
+/// サウンドデバイスが開けない場合はヌルシンクにフォールバックし、デバイスが使えるようになるのを待つ
+pub fn open_sink(config: &SinkConfig, playback: Arc<Mutex<PlaybackState>>) -> Result<Box<dyn AudioSink>> {
+    match open_sink_strict(config, playback.clone()) {
+        Ok(sink) => Ok(sink),
+        Err(e) if *config == SinkConfig::Device => {
+            log::warn!("[AudioSink] Failed to open output device ({}), falling back to null sink", e);
+            Ok(open_device_fallback(playback))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// 設定どおりのシンクを開く（フォールバックしない）
+pub fn open_sink_strict(config: &SinkConfig, playback: Arc<Mutex<PlaybackState>>) -> Result<Box<dyn AudioSink>> {
+    let sink: Box<dyn AudioSink> = match config {
+        SinkConfig::Device => Box::new(CpalSink::open(playback)?),
+        SinkConfig::Null { sample_rate } => Box::new(NullSink::open(playback, *sample_rate)),
+        SinkConfig::WavFile { path, sample_rate } => Box::new(WavFileSink::open(playback, path, *sample_rate)?),
+    };
+    log::info!("[AudioSink] Opened {}", sink.description());
+    Ok(sink)
+}
+
+/// サウンドデバイスの代わりに使うヌルシンクを開く
+pub fn open_device_fallback(playback: Arc<Mutex<PlaybackState>>) -> Box<dyn AudioSink> {
+    let mut sink = NullSink::open(playback, default_sample_rate());
+    sink.waiting_for_device = true;
+    log::info!("[AudioSink] Opened {}", sink.description());
+    Box::new(sink)
+}
+
+/// 既定の出力デバイス名（デバイスが無ければNone）
+fn default_output_device_name() -> Option<String> {
+    use cpal::traits::{DeviceTrait, HostTrait};
+
+    let device = cpal::default_host().default_output_device()?;
+    Some(device.name().unwrap_or_default())
+}
+
+/// 既定の出力デバイスを問い合わせる時期を管理する
+struct DevicePoll {
+    last: Cell<Instant>,
+}
+
+impl DevicePoll {
+    fn new() -> Self {
+        Self { last: Cell::new(Instant::now()) }
+    }
+
+    /// 前回から `DEVICE_POLL_INTERVAL` 経っていれば既定の出力デバイス名を問い合わせる
+    fn default_device_name(&self) -> Option<Option<String>> {
+        if self.last.get().elapsed() < DEVICE_POLL_INTERVAL {
+            return None;
+        }
+        self.last.set(Instant::now());
+        Some(default_output_device_name())
+    }
+}
+
+/// cpalによるサウンドデバイス出力
+pub struct CpalSink {
+    _stream: cpal::Stream,
+    description: String,
+    device_name: String,
+    failed: Arc<AtomicBool>,
+    poll: DevicePoll,
+}
+
+impl CpalSink {
+    pub fn open(playback: Arc<Mutex<PlaybackState>>) -> Result<Self> {
+        use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+        let host = cpal::default_host();
+        let device = host
+            .default_output_device()
+            .ok_or_else(|| anyhow::anyhow!("No output device found"))?;
+
+        let config = device.default_output_config()?;
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels();
+
+        log::info!("[AudioManager] Output config: {} Hz, {} channels", sample_rate, channels);
+        log::info!("[AudioManager] Input format: {} Hz, 1 channel (mono), f32le", INPUT_SAMPLE_RATE);
+        log::info!("[AudioManager] Resample ratio: {:.3}", sample_rate as f32 / INPUT_SAMPLE_RATE as f32);
+
+        // 音声ストリームを作成
+        let failed = Arc::new(AtomicBool::new(false));
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => Self::build_stream::<f32>(
+                &device,
+                &config.into(),
+                playback,
+                sample_rate,
+                channels,
+                failed.clone(),
+            )?,
+            cpal::SampleFormat::I16 => Self::build_stream::<i16>(
+                &device,
+                &config.into(),
+                playback,
+                sample_rate,
+                channels,
+                failed.clone(),
+            )?,
+            cpal::SampleFormat::U16 => Self::build_stream::<u16>(
+                &device,
+                &config.into(),
+                playback,
+                sample_rate,
+                channels,
+                failed.clone(),
+            )?,
+            sample_format => {
+                return Err(anyhow::anyhow!("Unsupported sample format: {:?}", sample_format));
+            }
+        };
+
+        stream.play()?;
+
+        let device_name = device.name().unwrap_or_default();
+        Ok(Self {
+            _stream: stream,
+            description: format!("output device \"{}\" ({} Hz, {} ch)", device_name, sample_rate, channels),
+            device_name,
+            failed,
+            poll: DevicePoll::new(),
+        })
+    }
+
+    fn build_stream<T>(
+        device: &cpal::Device,
+        config: &StreamConfig,
+        playback: Arc<Mutex<PlaybackState>>,
+        output_sample_rate: u32,
+        output_channels: u16,
+        failed: Arc<AtomicBool>,
+    ) -> Result<cpal::Stream>
+    where
+        T: cpal::Sample + cpal::SizedSample + cpal::FromSample<f32>,
+    {
+        use cpal::traits::DeviceTrait;
+
+        // 注意: これは入力サンプルの進み方を表す（出力1サンプルに対して入力を何サンプル進めるか）
+        let resample_ratio = INPUT_SAMPLE_RATE as f32 / output_sample_rate as f32;
+        let mut scratch: Vec<f32> = Vec::new();
+
+        log::info!("[AudioManager] Building stream - Input: {} Hz, Output: {} Hz, Input step per output sample: {:.3}",
+                   INPUT_SAMPLE_RATE, output_sample_rate, resample_ratio);
+
+        // デバイスの切断などでストリームが止まった場合は、音声スレッドに開き直してもらう
+        let err_fn = move |err: cpal::StreamError| {
+            match err {
+                cpal::StreamError::DeviceNotAvailable => {
+                    log::warn!("[AudioManager] Output device is no longer available");
+                }
+                err => log::error!("[AudioManager] Stream error: {}", err),
+            }
+            failed.store(true, Ordering::Relaxed);
+        };
+
+        let stream = device.build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                scratch.resize(data.len(), 0.0);
+                match playback.lock() {
+                    Ok(mut state) => state.render(&mut scratch, output_channels as usize, resample_ratio),
+                    Err(_) => scratch.fill(0.0),
+                }
+                for (sample, value) in data.iter_mut().zip(scratch.iter()) {
+                    *sample = T::from_sample(*value);
+                }
+            },
+            err_fn,
+            None,
+        )?;
+
+        Ok(stream)
+    }
+}
+
+impl AudioSink for CpalSink {
+    fn description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn rebuild_reason(&self) -> Option<RebuildReason> {
+        if self.failed.load(Ordering::Relaxed) {
+            return Some(RebuildReason::StreamError);
+        }
+        match self.poll.default_device_name()? {
+            Some(name) if name == self.device_name => None,
+            // デバイスが消えた場合もストリームを作り直す（失敗すればフォールバックする）
+            _ => Some(RebuildReason::DefaultDeviceChanged),
+        }
+    }
+}
+
+/// 実時間のペースで再生バッファを消費するスレッド
+struct RealtimeRenderer {
+    running: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl RealtimeRenderer {
+    fn spawn<F>(playback: Arc<Mutex<PlaybackState>>, sample_rate: u32, mut on_block: F) -> Self
+    where
+        F: FnMut(&[f32], bool) + Send + 'static,
+    {
+        let running = Arc::new(AtomicBool::new(true));
+        let running_clone = running.clone();
+        let handle = thread::spawn(move || {
+            let block_frames = (sample_rate as u64 * REALTIME_BLOCK_MS / 1000) as usize;
+            let block_duration = Duration::from_millis(REALTIME_BLOCK_MS);
+            let step = INPUT_SAMPLE_RATE as f32 / sample_rate as f32;
+            let mut block = vec![0.0f32; block_frames];
+            let mut deadline = Instant::now();
+
+            while running_clone.load(Ordering::Relaxed) {
+                let active = match playback.lock() {
+                    Ok(mut state) => {
+                        let active = !state.is_idle();
+                        state.render(&mut block, 1, step);
+                        active
+                    }
+                    Err(_) => break,
+                };
+                on_block(&block, active);
+
+                deadline += block_duration;
+                let now = Instant::now();
+                if deadline > now {
+                    thread::sleep(deadline - now);
+                } else {
+                    // 大きく遅れた場合は追いつこうとせずに基準を取り直す
+                    deadline = now;
+                }
+            }
+        });
+        Self { running, handle: Some(handle) }
+    }
+}
+
+impl Drop for RealtimeRenderer {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// 音を出さずに実時間で消費するシンク（ヘッドレス環境・CI向け）
+pub struct NullSink {
+    _renderer: RealtimeRenderer,
+    sample_rate: u32,
+    /// サウンドデバイスの代わりとして開かれ、デバイスが使えるようになるのを待っているか
+    waiting_for_device: bool,
+    poll: DevicePoll,
+}
+
+impl NullSink {
+    pub fn open(playback: Arc<Mutex<PlaybackState>>, sample_rate: u32) -> Self {
+        Self {
+            _renderer: RealtimeRenderer::spawn(playback, sample_rate, |_, _| {}),
+            sample_rate,
+            waiting_for_device: false,
+            poll: DevicePoll::new(),
+        }
+    }
+}
+
+impl AudioSink for NullSink {
+    fn description(&self) -> String {
+        if self.waiting_for_device {
+            format!("null sink ({} Hz, waiting for output device)", self.sample_rate)
+        } else {
+            format!("null sink ({} Hz)", self.sample_rate)
+        }
+    }
+
+    fn rebuild_reason(&self) -> Option<RebuildReason> {
+        if self.waiting_for_device && self.poll.default_device_name().flatten().is_some() {
+            Some(RebuildReason::DeviceAvailable)
+        } else {
+            None
+        }
+    }
+}
+
+/// 再生した音声をWAVファイルに書き出すシンク
+pub struct WavFileSink {
+    renderer: Option<RealtimeRenderer>,
+    path: String,
+    sample_rate: u32,
+}
+
+impl WavFileSink {
+    pub fn open(playback: Arc<Mutex<PlaybackState>>, path: &str, sample_rate: u32) -> Result<Self> {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut writer = Some(hound::WavWriter::create(path, spec)?);
+        let renderer = RealtimeRenderer::spawn(playback, sample_rate, move |block, active| {
+            if !active {
+                // 発話が無い間はファイルを書き進めず、書いた分をディスクに反映しておく
+                if let Some(w) = writer.as_mut() {
+                    let _ = w.flush();
+                }
+                return;
+            }
+            let failed = match writer.as_mut() {
+                Some(w) => block.iter().try_for_each(|&s| w.write_sample(s)).is_err(),
+                None => false,
+            };
+            if failed {
+                log::error!("[AudioSink] Failed to write to WAV file, disabling file output");
+                writer = None;
+            }
+        });
+        Ok(Self {
+            renderer: Some(renderer),
+            path: path.to_string(),
+            sample_rate,
+        })
+    }
+}
+
+impl AudioSink for WavFileSink {
+    fn description(&self) -> String {
+        format!("WAV file sink \"{}\" ({} Hz)", self.path, self.sample_rate)
+    }
+}
+
+impl Drop for WavFileSink {
+    fn drop(&mut self) {
+        // レンダラーのスレッドを止めてからファイルを閉じる（WavWriterはDropでヘッダーを確定する）
+        self.renderer.take();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::playback::AudioEvent;
+    use tokio::sync::broadcast;
+
+    #[test]
+    fn test_null_sink_consumes_in_real_time() {
+        let (events, mut receiver) = broadcast::channel(16);
+        let playback = Arc::new(Mutex::new(PlaybackState::new(events)));
+        {
+            let mut state = playback.lock().unwrap();
+            // 100ミリ秒分の音声
+            state.push("a", vec![0.1; INPUT_SAMPLE_RATE as usize / 10]);
+            state.finish("a");
+        }
+
+        let started = Instant::now();
+        let _sink = open_sink(&SinkConfig::default_null(), playback).unwrap();
+        loop {
+            match receiver.try_recv() {
+                Ok(AudioEvent::Finished { utterance_id, cancelled }) => {
+                    assert_eq!(utterance_id, "a");
+                    assert!(!cancelled);
+                    break;
+                }
+                Ok(_) | Err(broadcast::error::TryRecvError::Empty) => {
+                    assert!(started.elapsed() < Duration::from_secs(2), "再生が終わらない");
+                    thread::sleep(Duration::from_millis(5));
+                }
+                Err(e) => panic!("{:?}", e),
+            }
+        }
+        assert!(started.elapsed() >= Duration::from_millis(80));
+    }
+}
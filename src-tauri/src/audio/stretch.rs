@@ -0,0 +1,345 @@
+/**
+ * ピッチを保った再生速度の変更
+ * WSOLA（波形類似度に基づく重ね合わせ）でストリーミング処理し、発話の途中でも速度を変えられるようにする
+ */
+
+use std::collections::{HashMap, VecDeque};
+
+/// 再生速度の範囲
+pub const MIN_SPEED: f32 = 0.5;
+pub const MAX_SPEED: f32 = 3.0;
+
+/// フレーム長（サンプル数, 44.1kHzで約23ms）
+const FRAME_LENGTH: usize = 1024;
+/// 出力側のホップ長（50%オーバーラップ）
+const HOP: usize = FRAME_LENGTH / 2;
+/// 波形が最も揃う位置を探す範囲（±サンプル数）
+const SEARCH_RANGE: usize = 256;
+/// 類似度を計算するときの間引き間隔
+const CORRELATION_STRIDE: usize = 4;
+
+/// 速度を等倍とみなす誤差（この範囲では位置探索をせず、入力をそのまま再構成する）
+const UNITY_TOLERANCE: f32 = 1e-3;
+
+pub fn clamp_speed(speed: f32) -> f32 {
+    if speed.is_finite() {
+        speed.clamp(MIN_SPEED, MAX_SPEED)
+    } else {
+        1.0
+    }
+}
+
+/// ストリーミング型のWSOLAタイムストレッチャー
+pub struct TimeStretcher {
+    /// 未処理の入力（先頭が入力位置 `input_offset` に対応）
+    input: VecDeque<f32>,
+    input_offset: i64,
+    /// 受け取った実際の入力サンプル数（先頭の無音パディングを除く）
+    received: i64,
+    ended: bool,
+    /// 次フレームの名目上の入力位置
+    analysis_position: f64,
+    /// 直前のフレームを切り出した入力位置
+    previous_position: Option<i64>,
+    /// 重ね合わせ中の出力
+    overlap: Vec<f32>,
+    window: Vec<f32>,
+    /// 先頭のパディング分として捨てる出力サンプル数
+    discard: usize,
+    finished: bool,
+}
+
+impl Default for TimeStretcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimeStretcher {
+    pub fn new() -> Self {
+        // 周期的なハン窓（50%オーバーラップで和が1になる）
+        let window = (0..FRAME_LENGTH)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FRAME_LENGTH as f32).cos())
+            .collect();
+        // 先頭をホップ長だけ無音で埋め、最初のフレームの窓で音声が絞られないようにする
+        let mut input = VecDeque::new();
+        input.extend(std::iter::repeat_n(0.0, HOP));
+        Self {
+            input,
+            input_offset: -(HOP as i64),
+            received: 0,
+            ended: false,
+            analysis_position: -(HOP as f64),
+            previous_position: None,
+            overlap: vec![0.0; FRAME_LENGTH],
+            window,
+            discard: HOP,
+            finished: false,
+        }
+    }
+
+    pub fn push(&mut self, samples: &[f32]) {
+        self.input.extend(samples.iter().copied());
+        self.received += samples.len() as i64;
+    }
+
+    /// 入力の終わりを通知する（以降は末尾まで出力される）
+    pub fn end(&mut self) {
+        self.ended = true;
+    }
+
+    fn input_end(&self) -> i64 {
+        self.input_offset + self.input.len() as i64
+    }
+
+    fn sample_at(&self, position: i64) -> f32 {
+        let index = position - self.input_offset;
+        if index < 0 {
+            return 0.0;
+        }
+        self.input.get(index as usize).copied().unwrap_or(0.0)
+    }
+
+    /// 直前のフレームの自然な続きに最も波形が揃う切り出し位置を探す
+    fn best_position(&self, nominal: i64, speed: f32) -> i64 {
+        let Some(previous) = self.previous_position else {
+            return nominal;
+        };
+        let natural = previous + HOP as i64;
+        if (speed - 1.0).abs() < UNITY_TOLERANCE {
+            return natural;
+        }
+
+        let lowest = (nominal - SEARCH_RANGE as i64).max(self.input_offset);
+        let highest = nominal + SEARCH_RANGE as i64;
+        let mut best = (nominal, f32::MIN);
+        for candidate in lowest..=highest {
+            let mut correlation = 0.0;
+            for i in (0..HOP).step_by(CORRELATION_STRIDE) {
+                correlation += self.sample_at(natural + i as i64) * self.sample_at(candidate + i as i64);
+            }
+            if correlation > best.1 {
+                best = (candidate, correlation);
+            }
+        }
+        best.0
+    }
+
+    /// 1フレーム処理して最大でホップ長分の出力を追加する（入力が足りなければfalse）
+    fn process_frame(&mut self, speed: f32, output: &mut VecDeque<f32>) -> bool {
+        if self.finished {
+            return false;
+        }
+        let nominal = self.analysis_position.round() as i64;
+        let real_end = self.received;
+        if self.ended && nominal >= real_end {
+            // 重ね合わせ中の残りは実際の入力の終わりを越えるため出力しない
+            self.finished = true;
+            return false;
+        }
+        let required = nominal.max(self.previous_position.map_or(nominal, |p| p + HOP as i64))
+            + (SEARCH_RANGE + FRAME_LENGTH) as i64;
+        // 入力の終わり以降は無音として扱う
+        if !self.ended && required > self.input_end() {
+            return false;
+        }
+
+        let position = self.best_position(nominal, speed);
+        for i in 0..FRAME_LENGTH {
+            self.overlap[i] += self.sample_at(position + i as i64) * self.window[i];
+        }
+        self.previous_position = Some(position);
+
+        // 最後のフレームは入力の終わりに相当する長さで打ち切る
+        let mut emit = HOP;
+        if self.ended {
+            let remaining = ((real_end - nominal) as f32 / speed).ceil() as usize;
+            emit = emit.min(remaining);
+        }
+        let skip = self.discard.min(emit);
+        self.discard -= skip;
+        output.extend(self.overlap[skip..emit].iter().copied());
+        self.overlap.copy_within(HOP.., 0);
+        self.overlap[HOP..].fill(0.0);
+
+        // 最初のフレームの出力は先頭のパディング分として捨てるため、入力も等速で進める
+        self.analysis_position += if skip == HOP { HOP as f64 } else { HOP as f64 * speed as f64 };
+
+        // 以降のフレームで参照しない入力を捨てる
+        let keep_from = (self.analysis_position as i64 - SEARCH_RANGE as i64).min(position + HOP as i64);
+        let drop = (keep_from - self.input_offset).clamp(0, self.input.len() as i64) as usize;
+        self.input.drain(..drop);
+        self.input_offset += drop as i64;
+        true
+    }
+
+    /// 出力が `min_len` 以上になるまで（または入力が尽きるまで）処理する
+    pub fn fill(&mut self, speed: f32, output: &mut VecDeque<f32>, min_len: usize) {
+        let speed = clamp_speed(speed);
+        while output.len() < min_len {
+            if !self.process_frame(speed, output) {
+                break;
+            }
+        }
+    }
+
+    /// 一定の速度で音声全体を変換する（書き出し用）
+    pub fn stretch_all(samples: &[f32], speed: f32) -> Vec<f32> {
+        let speed = clamp_speed(speed);
+        if (speed - 1.0).abs() < UNITY_TOLERANCE {
+            return samples.to_vec();
+        }
+        let mut stretcher = Self::new();
+        stretcher.push(samples);
+        stretcher.end();
+        let mut output = VecDeque::new();
+        while stretcher.process_frame(speed, &mut output) {}
+        output.into()
+    }
+}
+
+/// 発話ごとの速度変換
+/// 音声再生スレッドで重い処理をしないよう、音声を受け取った側で変換してから再生バッファへ渡す
+/// 速度の変更は、その後に受け取った音声から反映される
+pub struct StretchFeeder {
+    /// 全体の再生速度
+    speed: f32,
+    /// レーンごとの速度の倍率
+    lane_speeds: HashMap<String, f32>,
+    /// 発話ごとの再生するレーンと変換器
+    utterances: HashMap<String, (String, TimeStretcher)>,
+}
+
+impl Default for StretchFeeder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StretchFeeder {
+    pub fn new() -> Self {
+        Self {
+            speed: 1.0,
+            lane_speeds: HashMap::new(),
+            utterances: HashMap::new(),
+        }
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = clamp_speed(speed);
+    }
+
+    pub fn set_lane_speed(&mut self, lane: &str, speed: f32) {
+        self.lane_speeds.insert(lane.to_string(), speed);
+    }
+
+    /// 発話を再生するレーンを登録する
+    pub fn open(&mut self, id: &str, lane: &str) {
+        self.utterances
+            .entry(id.to_string())
+            .or_insert_with(|| (lane.to_string(), TimeStretcher::new()));
+    }
+
+    fn speed_for(&self, lane: &str) -> f32 {
+        clamp_speed(self.speed * self.lane_speeds.get(lane).copied().unwrap_or(1.0))
+    }
+
+    /// 受け取った音声を現在の速度で変換し、変換できた分を返す
+    pub fn push(&mut self, id: &str, samples: &[f32]) -> Vec<f32> {
+        self.open(id, "");
+        let speed = self.speed_for(&self.utterances[id].0);
+        let (_, stretcher) = self.utterances.get_mut(id).expect("opened above");
+        stretcher.push(samples);
+        let mut output = VecDeque::new();
+        stretcher.fill(speed, &mut output, usize::MAX);
+        output.into()
+    }
+
+    /// 発話の終わりまで変換して残りを返す（変換器は破棄する）
+    pub fn finish(&mut self, id: &str) -> Vec<f32> {
+        let Some((lane, mut stretcher)) = self.utterances.remove(id) else {
+            return Vec::new();
+        };
+        stretcher.end();
+        let mut output = VecDeque::new();
+        stretcher.fill(self.speed_for(&lane), &mut output, usize::MAX);
+        output.into()
+    }
+
+    /// 再生しなくなった発話の変換器を破棄する
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.utterances.retain(|id, _| keep(id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(len: usize, frequency: f32) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * frequency * i as f32 / 44100.0).sin() * 0.5)
+            .collect()
+    }
+
+    /// ゼロ交差の数から周波数を推定する
+    fn estimate_frequency(samples: &[f32]) -> f32 {
+        let crossings = samples.windows(2).filter(|w| w[0] < 0.0 && w[1] >= 0.0).count();
+        crossings as f32 * 44100.0 / samples.len() as f32
+    }
+
+    #[test]
+    fn test_unity_speed_reconstructs_input() {
+        let input = sine(10_000, 220.0);
+        let mut stretcher = TimeStretcher::new();
+        stretcher.push(&input);
+        stretcher.end();
+        let mut output = VecDeque::new();
+        stretcher.fill(1.0, &mut output, usize::MAX);
+
+        assert_eq!(output.len(), input.len());
+        for (a, b) in input.iter().zip(output.iter()) {
+            assert!((a - b).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_stretch_keeps_pitch_and_changes_length() {
+        let input = sine(44100, 220.0);
+        for speed in [0.5, 1.5, 3.0] {
+            let output = TimeStretcher::stretch_all(&input, speed);
+            let expected = input.len() as f32 / speed;
+            assert!((output.len() as f32 - expected).abs() < expected * 0.02, "speed {}", speed);
+
+            let steady = &output[HOP * 2..output.len() - HOP * 2];
+            let frequency = estimate_frequency(steady);
+            assert!((frequency - 220.0).abs() < 10.0, "speed {}: {} Hz", speed, frequency);
+        }
+    }
+
+    #[test]
+    fn test_feeder_applies_lane_speed() {
+        let input = sine(44100, 220.0);
+        let mut feeder = StretchFeeder::new();
+        feeder.set_speed(1.5);
+        feeder.set_lane_speed("catch_up", 2.0);
+        feeder.open("a", "catch_up");
+        feeder.open("b", "main");
+
+        let mut outputs = Vec::new();
+        for id in ["a", "b"] {
+            let mut output = Vec::new();
+            for chunk in input.chunks(4410) {
+                output.extend(feeder.push(id, chunk));
+            }
+            output.extend(feeder.finish(id));
+            outputs.push(output.len() as f32);
+        }
+        for (len, speed) in outputs.into_iter().zip([3.0, 1.5]) {
+            let expected = input.len() as f32 / speed;
+            assert!((len - expected).abs() < expected * 0.02, "speed {}: {}", speed, len);
+        }
+        // 終わった発話の変換器は残らない
+        assert!(feeder.finish("a").is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+/**
+ * 発話前後の無音の除去
+ * 合成音声の冒頭・末尾の無音を削り、連続するコメントの読み上げの間延びを防ぐ
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// 音の立ち上がり前・減衰後に残す長さ（ミリ秒, 子音の頭や語尾を削らないため）
+const EDGE_MARGIN_MS: u32 = 5;
+
+/// 無音除去の設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrimConfig {
+    pub enabled: bool,
+    /// 無音とみなすレベル（dBFS）
+    pub threshold_db: f32,
+    /// この長さ以上続く無音だけを削る（ミリ秒）
+    pub min_gap_ms: u32,
+    /// 発話と発話の間に入れる無音（ミリ秒）
+    pub inter_utterance_gap_ms: u32,
+}
+
+impl Default for TrimConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_db: -45.0,
+            min_gap_ms: 30,
+            inter_utterance_gap_ms: 120,
+        }
+    }
+}
+
+fn ms_to_samples(ms: u32, sample_rate: u32) -> usize {
+    (ms as u64 * sample_rate as u64 / 1000) as usize
+}
+
+/// ストリーミングで冒頭・末尾の無音を削る
+/// 途中の無音は音が再開した時点でそのまま出力する
+pub struct SilenceTrimmer {
+    threshold: f32,
+    min_gap: usize,
+    margin: usize,
+    /// 最初の音が来たか
+    started: bool,
+    /// 冒頭で見送った無音の長さ
+    leading: usize,
+    /// 保留中の無音（冒頭では直近の余白分のみ, それ以降は全て）
+    pending: VecDeque<f32>,
+}
+
+impl SilenceTrimmer {
+    pub fn new(config: &TrimConfig, sample_rate: u32) -> Self {
+        Self {
+            threshold: 10f32.powf(config.threshold_db / 20.0),
+            min_gap: ms_to_samples(config.min_gap_ms, sample_rate),
+            margin: ms_to_samples(EDGE_MARGIN_MS, sample_rate),
+            started: false,
+            leading: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    pub fn process(&mut self, samples: &[f32]) -> Vec<f32> {
+        let mut output = Vec::with_capacity(samples.len());
+        for &sample in samples {
+            let silent = sample.abs() < self.threshold;
+            if !self.started {
+                if silent {
+                    self.leading += 1;
+                    self.pending.push_back(sample);
+                    // 短い無音は削らないため、最小長に達するまでは全て保留する
+                    if self.leading > self.min_gap.max(self.margin) {
+                        self.pending.pop_front();
+                    }
+                    continue;
+                }
+                self.started = true;
+                if self.leading > self.min_gap {
+                    let excess = self.pending.len().saturating_sub(self.margin);
+                    self.pending.drain(..excess);
+                }
+                output.extend(self.pending.drain(..));
+                output.push(sample);
+                continue;
+            }
+
+            if silent {
+                self.pending.push_back(sample);
+            } else {
+                output.extend(self.pending.drain(..));
+                output.push(sample);
+            }
+        }
+        output
+    }
+
+    /// 発話の終端で末尾の無音を削った残りを返す
+    pub fn finish(&mut self) -> Vec<f32> {
+        if !self.started {
+            // 全て無音だった発話は何も出力しない
+            self.pending.clear();
+            return Vec::new();
+        }
+        if self.pending.len() >= self.min_gap {
+            self.pending.truncate(self.margin);
+        }
+        self.pending.drain(..).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trims_leading_and_trailing_silence() {
+        let config = TrimConfig::default();
+        let sample_rate = 44100;
+        let margin = ms_to_samples(EDGE_MARGIN_MS, sample_rate);
+        let mut trimmer = SilenceTrimmer::new(&config, sample_rate);
+
+        let mut input = vec![0.0; 10_000];
+        input.extend(vec![0.5; 1000]);
+        // 途中の短い無音は残る
+        input.extend(vec![0.0; 500]);
+        input.extend(vec![0.5; 1000]);
+        input.extend(vec![0.0; 8000]);
+
+        let mut output = Vec::new();
+        for chunk in input.chunks(777) {
+            output.extend(trimmer.process(chunk));
+        }
+        output.extend(trimmer.finish());
+
+        assert_eq!(output.len(), margin + 1000 + 500 + 1000 + margin);
+        assert!(output[..margin].iter().all(|&s| s == 0.0));
+        assert_eq!(output[margin], 0.5);
+    }
+
+    #[test]
+    fn test_keeps_gaps_shorter_than_minimum() {
+        let config = TrimConfig { min_gap_ms: 100, ..TrimConfig::default() };
+        let mut trimmer = SilenceTrimmer::new(&config, 44100);
+
+        let mut input = vec![0.0; 1000];
+        input.extend(vec![0.5; 1000]);
+        let mut output = trimmer.process(&input);
+        output.extend(trimmer.finish());
+        assert_eq!(output.len(), 2000);
+    }
+}
@@ -0,0 +1,120 @@
+/**
+ * 棒読みちゃん互換サーバー
+ * 棒読みちゃんのソケット通信（既定ポート50001）で届いた読み上げ・操作を読み上げキューへ渡す
+ */
+
+pub mod protocol;
+
+use anyhow::{Context, Result};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::commands::tts::{clear_queue, set_paused, skip_lane, TTSState};
+use crate::queue::Admission;
+use protocol::{read_command, Command, Talk};
+
+/// コマンドを送り終えるまで待つ時間
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone)]
+pub struct BouyomiConfig {
+    pub port: u16,
+    pub enabled: bool,
+}
+
+impl Default for BouyomiConfig {
+    fn default() -> Self {
+        Self {
+            port: 50001,
+            enabled: true,
+        }
+    }
+}
+
+pub struct BouyomiServer {
+    config: BouyomiConfig,
+    app_handle: AppHandle,
+}
+
+impl BouyomiServer {
+    pub fn new(config: BouyomiConfig, app_handle: AppHandle) -> Self {
+        Self { config, app_handle }
+    }
+
+    /// 接続を待ち受ける（接続ごとに1つのコマンドを処理して閉じる）
+    pub async fn start(&self) -> Result<()> {
+        if !self.config.enabled {
+            log::info!("[Bouyomi] Server is disabled");
+            return Ok(());
+        }
+
+        let addr = SocketAddr::from(([127, 0, 0, 1], self.config.port));
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("棒読みちゃん互換サーバーを起動できません: {}", addr))?;
+        log::info!("[Bouyomi] Listening on {}", addr);
+
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    log::warn!("[Bouyomi] Failed to accept connection: {}", e);
+                    continue;
+                }
+            };
+            let app = self.app_handle.clone();
+            tokio::spawn(async move {
+                if let Err(e) = handle_connection(&app, stream).await {
+                    log::warn!("[Bouyomi] Rejected command from {}: {:#}", peer, e);
+                }
+            });
+        }
+    }
+}
+
+async fn handle_connection(app: &AppHandle, mut stream: TcpStream) -> Result<()> {
+    let command = tokio::time::timeout(READ_TIMEOUT, read_command(&mut stream))
+        .await
+        .context("コマンドの受信がタイムアウトしました")??;
+    let state = app.state::<TTSState>();
+    let state = state.inner();
+
+    match command {
+        Command::Talk(talk) => {
+            talk_to_queue(state, &talk);
+        }
+        Command::Pause => set_paused(state, true).await.map_err(anyhow::Error::msg)?,
+        Command::Resume => set_paused(state, false).await.map_err(anyhow::Error::msg)?,
+        Command::Skip => skip_lane(state, None).await.map_err(anyhow::Error::msg)?,
+        Command::Clear => {
+            clear_queue(state).await;
+        }
+        Command::GetPause => stream.write_u8(state.queue.is_paused() as u8).await?,
+        Command::GetNowPlaying => stream.write_u8(state.queue.is_speaking() as u8).await?,
+        Command::GetTaskCount => {
+            let count = state.queue.snapshot().pending.len();
+            stream.write_i32_le(i32::try_from(count).unwrap_or(i32::MAX)).await?;
+        }
+    }
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// 棒読みちゃん形式の読み上げをキューに積む
+pub fn talk_to_queue(state: &TTSState, talk: &Talk) -> Admission {
+    if talk.voice != 0 {
+        log::debug!("[Bouyomi] Ignoring voice {}", talk.voice);
+    }
+    let admission = state.queue.enqueue(talk.to_request());
+    match &admission {
+        Admission::Queued(item) | Admission::Merged(item) => {
+            log::info!("[Bouyomi] Enqueued {}", item.id);
+        }
+        Admission::Filtered(reason) => log::info!("[Bouyomi] Filtered talk ({:?})", reason),
+        Admission::Overflowed => log::warn!("[Bouyomi] Queue is full, dropped talk"),
+    }
+    admission
+}
@@ -0,0 +1,158 @@
+/**
+ * 棒読みちゃん互換のソケット通信
+ * コマンド・読み上げ設定・本文のバイナリ形式を解釈し、読み上げ要求に変換する
+ */
+
+use anyhow::{bail, Context, Result};
+use encoding_rs::{SHIFT_JIS, UTF_16LE};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::audio::effects::EffectConfig;
+use crate::queue::{Source, SpeechRequest};
+
+/// 受け付ける本文の最大バイト数
+const MAX_TEXT_BYTES: usize = 64 * 1024;
+
+/// 棒読みちゃんのコマンド
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Talk(Talk),
+    Pause,
+    Resume,
+    Skip,
+    Clear,
+    /// 一時停止中か（1バイトで返す）
+    GetPause,
+    /// 読み上げ中か（1バイトで返す）
+    GetNowPlaying,
+    /// 待機中の読み上げの件数（4バイトで返す）
+    GetTaskCount,
+}
+
+/// 読み上げの指示（速度・音程・音量は -1 で既定の設定）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Talk {
+    /// 速度（50〜300, 100が標準）
+    pub speed: i16,
+    /// 音程（50〜200, 100が標準）
+    pub tone: i16,
+    /// 音量（0〜100, 100が最大）
+    pub volume: i16,
+    /// 声の種類（0は画面の設定, 声の指定には対応しない）
+    pub voice: i16,
+    pub text: String,
+}
+
+impl Talk {
+    /// 読み上げ要求に変換する
+    /// 速度は声の速さ、音程はピッチシフト、音量は読み上げごとの音量に置き換える（声は読み上げの設定に従う）
+    pub fn to_request(&self) -> SpeechRequest {
+        let mut request = SpeechRequest {
+            source: Source::Bouyomi,
+            ..SpeechRequest::new(self.text.clone())
+        };
+        if self.speed > 0 {
+            request.voice_speed = Some(((self.speed as f32 - 100.0) / 100.0).clamp(-1.0, 1.0));
+        }
+        if self.tone > 0 && self.tone != 100 {
+            let semitones = (12.0 * (self.tone as f32 / 100.0).log2()).clamp(-12.0, 12.0);
+            request.effects = Some(vec![EffectConfig::PitchShift { semitones }]);
+        }
+        if self.volume >= 0 {
+            request.volume = Some((self.volume as f32 / 100.0).min(1.0));
+        }
+        request
+    }
+}
+
+/// 接続から1つのコマンドを読み取る
+pub async fn read_command<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Command> {
+    let command = reader.read_u16_le().await.context("コマンドを読み取れません")?;
+    Ok(match command {
+        0x0001 => Command::Talk(read_talk(reader).await?),
+        0x0010 => Command::Pause,
+        0x0020 => Command::Resume,
+        0x0030 => Command::Skip,
+        0x0040 => Command::Clear,
+        0x0110 => Command::GetPause,
+        0x0120 => Command::GetNowPlaying,
+        0x0130 => Command::GetTaskCount,
+        other => bail!("不明なコマンドです: 0x{:04x}", other),
+    })
+}
+
+async fn read_talk<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Talk> {
+    let speed = reader.read_i16_le().await?;
+    let tone = reader.read_i16_le().await?;
+    let volume = reader.read_i16_le().await?;
+    let voice = reader.read_i16_le().await?;
+    let encoding = reader.read_u8().await?;
+    let length = reader.read_i32_le().await?;
+    let length = usize::try_from(length)
+        .ok()
+        .filter(|length| *length <= MAX_TEXT_BYTES)
+        .with_context(|| format!("本文の長さが不正です: {}", length))?;
+    let mut bytes = vec![0; length];
+    reader.read_exact(&mut bytes).await.context("本文を読み取れません")?;
+    Ok(Talk {
+        speed,
+        tone,
+        volume,
+        voice,
+        text: decode_text(encoding, &bytes)?,
+    })
+}
+
+/// 本文を文字コードに従って読む（0: UTF-8, 1: UTF-16LE, 2: Shift_JIS）
+fn decode_text(encoding: u8, bytes: &[u8]) -> Result<String> {
+    let text = match encoding {
+        0 => String::from_utf8_lossy(bytes).into_owned(),
+        1 => UTF_16LE.decode_without_bom_handling(bytes).0.into_owned(),
+        2 => SHIFT_JIS.decode_without_bom_handling(bytes).0.into_owned(),
+        other => bail!("不明な文字コードです: {}", other),
+    };
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn talk_packet(speed: i16, tone: i16, encoding: u8, text: &[u8]) -> Vec<u8> {
+        let mut packet = 0x0001u16.to_le_bytes().to_vec();
+        for value in [speed, tone, -1, 0] {
+            packet.extend(value.to_le_bytes());
+        }
+        packet.push(encoding);
+        packet.extend((text.len() as i32).to_le_bytes());
+        packet.extend(text);
+        packet
+    }
+
+    #[tokio::test]
+    async fn test_read_talk_command() {
+        let (shift_jis, _, _) = SHIFT_JIS.encode("こんにちは");
+        let packet = talk_packet(200, 200, 2, &shift_jis);
+        let Command::Talk(talk) = read_command(&mut packet.as_slice()).await.unwrap() else {
+            panic!("読み上げのコマンドではありません");
+        };
+        assert_eq!(talk.text, "こんにちは");
+
+        let request = talk.to_request();
+        assert_eq!(request.source, Source::Bouyomi);
+        assert_eq!(request.voice_speed, Some(1.0));
+        assert_eq!(request.effects, Some(vec![EffectConfig::PitchShift { semitones: 12.0 }]));
+        assert_eq!(Talk { volume: 50, ..talk }.to_request().volume, Some(0.5));
+
+        // 既定の設定のままなら何も上書きしない
+        let packet = talk_packet(-1, -1, 0, "テスト".as_bytes());
+        let Command::Talk(talk) = read_command(&mut packet.as_slice()).await.unwrap() else {
+            panic!("読み上げのコマンドではありません");
+        };
+        let request = talk.to_request();
+        assert!(request.voice_speed.is_none() && request.effects.is_none() && request.volume.is_none());
+
+        assert_eq!(read_command(&mut [0x10u8, 0x00].as_slice()).await.unwrap(), Command::Pause);
+        assert!(read_command(&mut [0xffu8, 0x00].as_slice()).await.is_err());
+    }
+}
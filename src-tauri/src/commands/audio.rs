@@ -0,0 +1,50 @@
+/**
+ * 音声処理設定関連のTauriコマンド
+ * ラウドネス正規化などの再生パイプライン設定を管理
+ */
+
+use crate::audio::config::AudioConfig;
+use crate::audio::jitter::PlaybackStats;
+use tauri::State;
+
+use super::tts::TTSState;
+
+#[tauri::command]
+pub async fn get_audio_config(state: State<'_, TTSState>) -> Result<AudioConfig, String> {
+    let config = state.audio_config.lock().await;
+    Ok(config.clone())
+}
+
+#[tauri::command]
+pub async fn update_audio_config(
+    state: State<'_, TTSState>,
+    config: AudioConfig,
+) -> Result<(), String> {
+    config.validate()?;
+
+    log::info!("音声処理設定を更新: {:?}", config);
+
+    // 再生中のAudioManagerにも反映
+    let audio_manager = state.audio_manager.lock().await;
+    if let Some(manager) = audio_manager.as_ref() {
+        manager.set_fade(config.fade.clone()).map_err(|e| e.to_string())?;
+        manager.set_ducking(config.ducking.clone()).map_err(|e| e.to_string())?;
+        manager.set_meter(config.meter.clone()).map_err(|e| e.to_string())?;
+        manager.set_jitter(config.jitter.clone()).map_err(|e| e.to_string())?;
+        if state.audio_config.lock().await.sink != config.sink {
+            manager.set_sink(config.sink.clone()).map_err(|e| e.to_string())?;
+        }
+    }
+    drop(audio_manager);
+
+    let mut current = state.audio_config.lock().await;
+    *current = config;
+    Ok(())
+}
+
+/// 再生バッファの統計を取得（再生前はすべて0）
+#[tauri::command]
+pub async fn get_audio_stats(state: State<'_, TTSState>) -> Result<PlaybackStats, String> {
+    let audio_manager = state.audio_manager.lock().await;
+    Ok(audio_manager.as_ref().map(|manager| manager.stats()).unwrap_or_default())
+}
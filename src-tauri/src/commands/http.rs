@@ -4,14 +4,47 @@
  */
 
 use crate::http::{HttpServer, HttpServerConfig};
+use crate::tts::storage::{app_data_dir, HttpTokenStorage};
 use serde_json::Value;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::{AppHandle, State};
 use tokio::sync::RwLock;
 
+const HTTP_CONFIG_FILE: &str = "http_config.json";
+
+fn config_path() -> Result<PathBuf, String> {
+    Ok(app_data_dir().map_err(|e| e.to_string())?.join(HTTP_CONFIG_FILE))
+}
+
 #[derive(Default)]
 pub struct HttpServerState {
     pub config: Arc<RwLock<HttpServerConfig>>,
+    /// アクセストークン（起動時に読み込み、初回は生成する）
+    token: std::sync::RwLock<Option<String>>,
+}
+
+impl HttpServerState {
+    pub fn token(&self) -> Option<String> {
+        self.token.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone()
+    }
+
+    fn set_token(&self, token: String) {
+        *self.token.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(token);
+    }
+
+    /// 保存された設定を読み込む（起動時、サーバーを起動する前に1度だけ呼ぶ）
+    pub fn load_config(&self) -> Result<(), String> {
+        *self.config.blocking_write() = HttpServerConfig::load(&config_path()?);
+        Ok(())
+    }
+
+    /// 保存されたアクセストークンを読み込む（初回は生成して保存する）
+    pub fn load_token(&self) -> Result<(), String> {
+        let token = HttpTokenStorage::get_or_create().map_err(|e| e.to_string())?;
+        self.set_token(token);
+        Ok(())
+    }
 }
 
 #[tauri::command]
@@ -25,18 +58,47 @@ pub async fn update_http_config(
     state: State<'_, HttpServerState>,
     port: u16,
     enabled: bool,
+    require_token: Option<bool>,
+    allowed_origins: Option<Vec<String>>,
     app: AppHandle,
 ) -> Result<(), String> {
     // ポート番号の検証
     HttpServerConfig::validate_port(port)?;
+    let allowed_origins = allowed_origins.map(|origins| {
+        origins
+            .into_iter()
+            .map(|origin| origin.trim().to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect::<Vec<_>>()
+    });
+    if let Some(origins) = allowed_origins.as_deref() {
+        HttpServerConfig::validate_origins(origins)?;
+    }
 
-    // 設定を更新
+    // 設定を更新（トークンと許可するオリジンは次のリクエストから反映される）
     let mut config = state.config.write().await;
     config.port = port;
     config.enabled = enabled;
+    if let Some(require_token) = require_token {
+        config.require_token = require_token;
+    }
+    if let Some(origins) = allowed_origins {
+        config.allowed_origins = origins;
+    }
 
-    // 設定を保存（実際のアプリケーションではファイルや設定ストアに保存）
-    log::info!("HTTPサーバー設定を更新: port={}, enabled={}", port, enabled);
+    log::info!(
+        "HTTPサーバー設定を更新: port={}, enabled={}, require_token={}, allowed_origins={:?}",
+        port, enabled, config.require_token, config.allowed_origins
+    );
+    let saved = config.clone();
+    drop(config);
+
+    // 設定を保存（次回の起動時に読み込む）
+    let path = config_path()?;
+    tokio::task::spawn_blocking(move || saved.save(&path))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("HTTPサーバー設定の保存に失敗しました: {}", e))?;
 
     // サーバーの再起動が必要な場合はここで行う
     if enabled {
@@ -77,4 +139,23 @@ pub async fn start_http_server(
     });
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// HTTPサーバーのアクセストークンを取得する
+#[tauri::command]
+pub async fn get_http_token(state: State<'_, HttpServerState>) -> Result<String, String> {
+    if let Some(token) = state.token() {
+        return Ok(token);
+    }
+    state.load_token()?;
+    state.token().ok_or_else(|| "アクセストークンを読み込めません".to_string())
+}
+
+/// HTTPサーバーのアクセストークンを作り直す（古いトークンはすぐに使えなくなる）
+#[tauri::command]
+pub async fn regenerate_http_token(state: State<'_, HttpServerState>) -> Result<String, String> {
+    let token = HttpTokenStorage::regenerate().map_err(|e| e.to_string())?;
+    state.set_token(token.clone());
+    log::info!("HTTPサーバーのアクセストークンを作り直しました");
+    Ok(token)
+}
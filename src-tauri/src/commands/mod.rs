@@ -4,5 +4,8 @@
  */
 
 pub mod tts;
+pub mod audio;
+pub mod queue;
+pub mod schedule;
 pub mod http;
 pub mod test;
\ No newline at end of file
@@ -0,0 +1,38 @@
+/**
+ * 読み上げキュー関連のTauriコマンド
+ * キューの設定と起動時の復元結果を管理
+ */
+
+use crate::queue::{FilterReport, QueueConfig, RestoreReport};
+use tauri::State;
+
+use super::tts::TTSState;
+
+#[tauri::command]
+pub async fn get_queue_config(state: State<'_, TTSState>) -> Result<QueueConfig, String> {
+    Ok(state.queue.config())
+}
+
+#[tauri::command]
+pub async fn update_queue_config(
+    state: State<'_, TTSState>,
+    config: QueueConfig,
+) -> Result<(), String> {
+    config.validate()?;
+    log::info!("読み上げキューの設定を更新: {:?}", config);
+    state.queue.set_config(config)
+}
+
+/// 連投・荒らし対策で除外・統合したメッセージの集計を取得する
+#[tauri::command]
+pub async fn get_queue_filter_report(state: State<'_, TTSState>) -> Result<FilterReport, String> {
+    Ok(state.queue.filter_report())
+}
+
+/// 起動時に復元した読み上げと、失われた読み上げを取得する
+#[tauri::command]
+pub async fn get_queue_restore_report(
+    state: State<'_, TTSState>,
+) -> Result<Option<RestoreReport>, String> {
+    Ok(state.queue.restore_report())
+}
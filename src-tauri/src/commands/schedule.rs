@@ -0,0 +1,47 @@
+/**
+ * 予約読み上げ関連のTauriコマンド
+ * 予約の一覧・追加・削除と予定表（.ics）の取り込みを管理
+ */
+
+use crate::scheduler::{IcsImport, NewSchedule, ScheduledJob, Scheduler};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_schedules(scheduler: State<'_, Scheduler>) -> Result<Vec<ScheduledJob>, String> {
+    Ok(scheduler.list())
+}
+
+#[tauri::command]
+pub async fn add_schedule(
+    scheduler: State<'_, Scheduler>,
+    schedule: NewSchedule,
+) -> Result<ScheduledJob, String> {
+    scheduler.add(schedule)
+}
+
+#[tauri::command]
+pub async fn remove_schedule(scheduler: State<'_, Scheduler>, id: String) -> Result<(), String> {
+    if scheduler.remove(&id) {
+        Ok(())
+    } else {
+        Err("予約が見つかりません".to_string())
+    }
+}
+
+#[tauri::command]
+pub async fn set_schedule_enabled(
+    scheduler: State<'_, Scheduler>,
+    id: String,
+    enabled: bool,
+) -> Result<ScheduledJob, String> {
+    scheduler.set_enabled(&id, enabled)
+}
+
+/// 予定表ファイル（.ics）のアラームを予約として取り込む
+#[tauri::command]
+pub async fn import_ics_file(scheduler: State<'_, Scheduler>, path: String) -> Result<IcsImport, String> {
+    let content = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("予定表ファイルを読み込めませんでした: {}", e))?;
+    scheduler.import_ics(&content)
+}
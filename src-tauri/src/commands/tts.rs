@@ -4,33 +4,55 @@
  */
 
 use crate::tts::{
-    client::CartesiaClient,
     config::ApiKeyManager,
     storage::ApiKeyStorage,
+    synthesizer::render_speech,
     TTSConfig
 };
+use crate::audio::config::AudioConfig;
+use crate::audio::effects::EffectConfig;
+use crate::audio::export::{self, ExportFormat};
 use crate::audio::manager::AudioManager;
+use crate::audio::meter::{AudioLevel, LevelThrottle};
+use crate::audio::playback::{AudioEvent, INPUT_SAMPLE_RATE};
+use crate::audio::stretch::clamp_speed;
+use crate::queue::prefetch::Prefetcher;
+use crate::queue::tracker::SpeechStatus;
+use crate::queue::item::QueueItem;
+use crate::queue::{Admission, Interrupt, Priority, QueueSnapshot, Slot, SpeechQueue, SpeechRequest};
 use tauri::{AppHandle, Emitter, State};
-use tokio::sync::{Mutex, mpsc, oneshot};
+use tokio::sync::{Mutex, broadcast, oneshot};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use log::info;
 
 pub struct TTSState {
     pub config: Arc<Mutex<TTSConfig>>,
-    pub is_synthesizing: Arc<Mutex<bool>>,
     pub api_key: Arc<Mutex<Option<String>>>,
-    pub cancel_tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
+    /// 合成中の読み上げごとのキャンセル信号（読み上げIDごと）
+    pub synthesis_cancels: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
     pub audio_manager: Arc<Mutex<Option<AudioManager>>>,
+    pub audio_config: Arc<Mutex<AudioConfig>>,
+    /// 間引き済みの出力レベル（外部WebSocket向け）
+    pub level_events: broadcast::Sender<AudioLevel>,
+    /// Tauriコマンド・HTTPサーバーで共有する読み上げキュー
+    pub queue: SpeechQueue,
+    /// 先読みした音声
+    pub prefetcher: Prefetcher,
 }
 
 impl Default for TTSState {
     fn default() -> Self {
         Self {
             config: Arc::new(Mutex::new(TTSConfig::default())),
-            is_synthesizing: Arc::new(Mutex::new(false)),
             api_key: Arc::new(Mutex::new(None)),
-            cancel_tx: Arc::new(Mutex::new(None)),
+            synthesis_cancels: Arc::new(Mutex::new(HashMap::new())),
             audio_manager: Arc::new(Mutex::new(None)),
+            audio_config: Arc::new(Mutex::new(AudioConfig::default())),
+            level_events: broadcast::channel(64).0,
+            queue: SpeechQueue::new(),
+            prefetcher: Prefetcher::default(),
         }
     }
 }
@@ -119,7 +141,12 @@ pub async fn update_tts_config(
         config.voice_id = voice_id;
     }
     if let Some(speed) = speed {
-        config.speed = speed.clamp(0.5, 2.0);
+        config.speed = clamp_speed(speed);
+        // 再生中の発話の速度も更新
+        let audio_manager = state.audio_manager.lock().await;
+        if let Some(manager) = audio_manager.as_ref() {
+            let _ = manager.set_speed(config.speed);
+        }
     }
     if let Some(volume) = volume {
         config.volume = volume.clamp(0.0, 1.0);
@@ -145,153 +172,322 @@ pub async fn get_tts_config(state: State<'_, TTSState>) -> Result<TTSConfig, Str
     Ok(config.clone())
 }
 
+/// 声ごとのエフェクト設定を更新する（空のリストで解除）
+#[tauri::command]
+pub async fn set_voice_effects(
+    state: State<'_, TTSState>,
+    voice_id: String,
+    effects: Vec<EffectConfig>,
+) -> Result<(), String> {
+    let mut config = state.config.lock().await;
+    if effects.is_empty() {
+        config.voice_effects.remove(&voice_id);
+    } else {
+        config.voice_effects.insert(voice_id, effects);
+    }
+    Ok(())
+}
+
+/// テキストを読み上げキューに積む
+/// `effects` を指定した場合は声ごとのエフェクト設定の代わりに使用する
+/// 積んだ読み上げのIDを返す（再生イベントの utterance_id と同じ）
 #[tauri::command]
 pub async fn synthesize_speech(
-    app: AppHandle,
     state: State<'_, TTSState>,
     text: String,
-) -> Result<(), String> {
-    eprintln!("synthesize_speech command called with text: {}", text);
-    // 既に合成中の場合はエラー
-    let mut is_synthesizing = state.is_synthesizing.lock().await;
-    if *is_synthesizing {
-        return Err("既に音声合成が実行中です".to_string());
+    effects: Option<Vec<EffectConfig>>,
+    priority: Option<String>,
+    lane: Option<String>,
+) -> Result<String, String> {
+    if text.trim().is_empty() {
+        return Err("テキストが空です".to_string());
     }
-    *is_synthesizing = true;
-    drop(is_synthesizing);
-    
-    // キャンセル用のチャンネルを作成
-    let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
-    let mut cancel_sender = state.cancel_tx.lock().await;
-    *cancel_sender = Some(cancel_tx);
-    drop(cancel_sender);
-    
+    state.queue.check_lane(lane.as_deref())?;
+    let admission = state.queue.enqueue(SpeechRequest {
+        priority: Priority::parse(priority.as_deref()),
+        lane,
+        effects,
+        ..SpeechRequest::new(text)
+    });
+    match admission {
+        Admission::Queued(item) | Admission::Merged(item) => {
+            info!("[TTS Command] Enqueued {}", item.id);
+            Ok(item.id)
+        }
+        Admission::Filtered(_) => Err("連投・荒らし対策により読み上げませんでした".to_string()),
+        Admission::Overflowed => Err("キューがいっぱいのため読み上げませんでした".to_string()),
+    }
+}
+
+/// 読み上げ中・待機中の読み上げを取得する
+#[tauri::command]
+pub async fn get_speech_queue(state: State<'_, TTSState>) -> Result<QueueSnapshot, String> {
+    Ok(state.queue.snapshot())
+}
+
+/// 読み上げの進み具合を取得する（記録が残っていなければNone）
+#[tauri::command]
+pub async fn get_speech_status(state: State<'_, TTSState>, id: String) -> Result<Option<SpeechStatus>, String> {
+    Ok(state.queue.tracker().get(&id))
+}
+
+/// 音声を再生せずに合成し、ファイルへ書き出す
+/// 書き出したバイト数を返す
+#[tauri::command]
+pub async fn synthesize_to_file(
+    state: State<'_, TTSState>,
+    text: String,
+    path: String,
+    format: Option<ExportFormat>,
+    effects: Option<Vec<EffectConfig>>,
+) -> Result<usize, String> {
     let config = state.config.lock().await.clone();
-    let is_synthesizing_clone = state.is_synthesizing.clone();
+    let bytes = synthesize_encoded(state.inner(), config, &text, format.unwrap_or_default(), effects).await?;
+    
+    tokio::fs::write(&path, &bytes)
+        .await
+        .map_err(|e| format!("ファイルの書き込みに失敗しました: {}", e))?;
+    info!("[TTS Command] Exported {} bytes to {}", bytes.len(), path);
+    Ok(bytes.len())
+}
+
+/// 読み上げを停止し、待機中の読み上げも全て取り消す
+/// `soft` が true の場合は読み上げ中の単語の切れ目まで待ってからフェードアウトする
+#[tauri::command]
+pub async fn stop_speech(state: State<'_, TTSState>, soft: Option<bool>) -> Result<(), String> {
+    info!("[TTS Command] stop_speech called (soft: {:?})", soft);
+    stop_all(&state, soft.unwrap_or(false)).await;
+    Ok(())
+}
+
+/// 読み上げを停止し、待機中の読み上げも全て取り消す（Tauriコマンド・HTTPで共通）
+pub async fn stop_all(state: &TTSState, soft: bool) {
+    let cleared = state.queue.clear();
+    if !cleared.is_empty() {
+        info!("[TTS Command] Cleared {} queued items", cleared.len());
+    }
     
-    // 音声データ受信用のチャンネルを作成
-    let (audio_tx, mut audio_rx) = mpsc::channel::<Vec<u8>>(100);
+    // 中断中の読み上げも含め、合成中の読み上げを全てキャンセル
+    for (_, sender) in state.synthesis_cancels.lock().await.drain() {
+        let _ = sender.send(());
+    }
     
-    // AudioManagerを作成・初期化
-    let manager = AudioManager::new().map_err(|e| format!("音声マネージャーの初期化に失敗しました: {}", e))?;
-    manager.set_volume(config.volume).map_err(|e| format!("音量設定に失敗しました: {}", e))?;
+    // 音声再生を停止
+    let audio_manager = state.audio_manager.lock().await;
+    if let Some(manager) = audio_manager.as_ref() {
+        let _ = if soft { manager.soft_stop() } else { manager.stop() };
+        info!("[TTS Command] Audio playback stopped");
+    }
+    drop(audio_manager);
     
-    // 状態を更新
+    state.queue.interrupt_all(Interrupt::Cancel);
+}
+
+/// レーン（未指定なら既定のレーン）で再生中の発話をフェードアウトして次の読み上げへ進む
+#[tauri::command]
+pub async fn skip_speech(state: State<'_, TTSState>, lane: Option<String>) -> Result<(), String> {
+    skip_lane(&state, lane.as_deref()).await
+}
+
+/// レーンで再生中の発話をスキップする（Tauriコマンド・HTTPで共通）
+pub async fn skip_lane(state: &TTSState, lane: Option<&str>) -> Result<(), String> {
+    state.queue.check_lane(lane)?;
+    let lane = state
+        .queue
+        .lane_config(lane.unwrap_or_default())
+        .ok_or_else(|| "レーンが設定されていません".to_string())?;
+    if let Some(item) = state.queue.interrupt(&Slot::Main(lane.name.clone()), Interrupt::Cancel) {
+        cancel_synthesis(state, &item.id).await;
+    }
+    let audio_manager = state.audio_manager.lock().await;
+    if let Some(manager) = audio_manager.as_ref() {
+        manager.skip(&lane.name).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// 読み上げ中の音声をフェードアウトして一時停止し、次の読み上げも始めない
+#[tauri::command]
+pub async fn pause_speech(state: State<'_, TTSState>) -> Result<(), String> {
+    set_paused(&state, true).await
+}
+
+/// 一時停止した読み上げを続きから再生する
+#[tauri::command]
+pub async fn resume_speech(state: State<'_, TTSState>) -> Result<(), String> {
+    set_paused(&state, false).await
+}
+
+/// 一時停止する・再開する（Tauriコマンド・HTTPで共通）
+pub async fn set_paused(state: &TTSState, paused: bool) -> Result<(), String> {
+    info!("[TTS Command] {} speech", if paused { "Pausing" } else { "Resuming" });
+    state.queue.set_paused(paused);
+    let audio_manager = state.audio_manager.lock().await;
+    if let Some(manager) = audio_manager.as_ref() {
+        manager.set_paused(paused).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// 待機中の読み上げを取り除く・読み上げ中ならフェードアウトして止める
+/// 見つからなければfalse
+#[tauri::command]
+pub async fn cancel_speech(state: State<'_, TTSState>, id: String) -> Result<bool, String> {
+    Ok(cancel_item(&state, &id).await)
+}
+
+/// 読み上げを1件取り消す（Tauriコマンド・HTTPで共通）
+pub async fn cancel_item(state: &TTSState, id: &str) -> bool {
+    // 待機中でも、中断中・先読み中なら音声と合成が残っているため同じように止める
+    if state.queue.remove(id).is_some() {
+        info!("[TTS Command] Removed {} from queue", id);
+    } else if state.queue.interrupt_item(id, Interrupt::Cancel).is_none() {
+        return false;
+    }
+    cancel_synthesis(state, id).await;
+    let audio_manager = state.audio_manager.lock().await;
+    if let Some(manager) = audio_manager.as_ref() {
+        let _ = manager.stop_utterance(id);
+    }
+    info!("[TTS Command] Cancelled {}", id);
+    true
+}
+
+/// 待機中の読み上げを全て取り除く（読み上げ中の読み上げはそのまま）
+/// 取り除いた件数を返す
+#[tauri::command]
+pub async fn clear_speech_queue(state: State<'_, TTSState>) -> Result<usize, String> {
+    Ok(clear_queue(&state).await.len())
+}
+
+/// 待機中の読み上げを全て取り除き、中断中の読み上げの音声と合成も止める
+/// （Tauriコマンド・HTTP・WebSocket・棒読みちゃん互換で共通）
+pub async fn clear_queue(state: &TTSState) -> Vec<QueueItem> {
+    let cleared = state.queue.clear();
+    info!("[TTS Command] Cleared {} queued items", cleared.len());
+    let suspended: Vec<&str> = cleared.iter().filter(|item| item.suspended).map(|item| item.id.as_str()).collect();
+    if suspended.is_empty() {
+        return cleared;
+    }
+    for id in &suspended {
+        cancel_synthesis(state, id).await;
+    }
+    let audio_manager = state.audio_manager.lock().await;
+    if let Some(manager) = audio_manager.as_ref() {
+        for id in suspended {
+            let _ = manager.stop_utterance(id);
+        }
+    }
+    drop(audio_manager);
+    cleared
+}
+
+/// 合成中の読み上げにキャンセル信号を送る
+pub async fn cancel_synthesis(state: &TTSState, utterance_id: &str) {
+    if let Some(sender) = state.synthesis_cancels.lock().await.remove(utterance_id) {
+        let _ = sender.send(());
+        info!("[TTS Command] Cancel signal sent to {}", utterance_id);
+    }
+}
+
+/// AudioManagerが未作成なら作成し、再生イベントをフロントエンドへ転送する
+pub async fn ensure_audio_manager(state: &TTSState, app: &AppHandle) -> Result<(), String> {
     let mut audio_manager_lock = state.audio_manager.lock().await;
+    match audio_manager_lock.as_ref() {
+        Some(manager) if manager.is_alive() => return Ok(()),
+        Some(_) => log::error!("[TTS Command] Audio thread stopped, recreating audio manager"),
+        None => {}
+    }
+    
+    let audio_config = state.audio_config.lock().await.clone();
+    let manager = AudioManager::new(audio_config.sink.clone()).map_err(|e| format!("音声マネージャーの初期化に失敗しました: {}", e))?;
+    manager.set_fade(audio_config.fade.clone()).map_err(|e| e.to_string())?;
+    manager.set_ducking(audio_config.ducking.clone()).map_err(|e| e.to_string())?;
+    manager.set_meter(audio_config.meter.clone()).map_err(|e| e.to_string())?;
+    manager.set_jitter(audio_config.jitter.clone()).map_err(|e| e.to_string())?;
+    manager.set_paused(state.queue.is_paused()).map_err(|e| e.to_string())?;
+    let mut events = manager.subscribe();
     *audio_manager_lock = Some(manager);
-    drop(audio_manager_lock);
     
-    // PCMデータ変換とRust側での再生タスク
-    let app_clone = app.clone();
-    let audio_manager_state = state.audio_manager.clone();
+    let app = app.clone();
+    let audio_config = state.audio_config.clone();
+    let level_events = state.level_events.clone();
     tokio::spawn(async move {
-        let mut chunk_count = 0;
-        while let Some(audio_data) = audio_rx.recv().await {
-            chunk_count += 1;
-            eprintln!("Processing audio chunk #{} ({} bytes)", chunk_count, audio_data.len());
-            
-            // PCM f32le形式のバイトデータをf32配列に変換
-            let mut f32_samples = Vec::with_capacity(audio_data.len() / 4);
-            for chunk in audio_data.chunks_exact(4) {
-                if let Ok(bytes) = <[u8; 4]>::try_from(chunk) {
-                    let sample = f32::from_le_bytes(bytes);
-                    f32_samples.push(sample);
+        let mut throttle = LevelThrottle::new();
+        loop {
+            match events.recv().await {
+                Ok(AudioEvent::Level(level)) => {
+                    // 出力レベルは設定した間隔に間引いて通知する
+                    let interval_ms = audio_config.lock().await.meter.interval_ms;
+                    if let Some(level) = throttle.offer(level, Duration::from_millis(interval_ms as u64)) {
+                        let _ = app.emit("audio-level", &level);
+                        let _ = level_events.send(level);
+                    }
                 }
-            }
-            
-            // Rust側のプレイヤーに送信
-            let manager_lock = audio_manager_state.lock().await;
-            if let Some(manager) = manager_lock.as_ref() {
-                if let Err(e) = manager.play_audio(f32_samples) {
-                    eprintln!("音声データの送信に失敗しました: {}", e);
+                Ok(event) => {
+                    if let AudioEvent::Underrun { utterance_id, underruns, target_ms } = &event {
+                        log::warn!("[AudioManager] Underrun in {} (total {}, target {} ms)",
+                                   utterance_id, underruns, target_ms);
+                    }
+                    let _ = app.emit(event.name(), &event);
                 }
-            }
-        }
-        
-        eprintln!("All audio chunks processed. Emitting audio-complete event");
-        // 合成完了を通知
-        let _ = app_clone.emit("audio-complete", ());
-        
-        // 合成完了フラグをリセット
-        let mut is_synthesizing = is_synthesizing_clone.lock().await;
-        *is_synthesizing = false;
-        
-        // オーディオマネージャーをクリーンアップ
-        let mut audio_manager_lock = audio_manager_state.lock().await;
-        *audio_manager_lock = None;
-    });
-    
-    // APIキーを取得
-    let api_key = {
-        let stored_key = state.api_key.lock().await;
-        eprintln!("Checking stored API key: {:?}", stored_key.is_some());
-        match stored_key.as_ref() {
-            Some(key) => {
-                eprintln!("Using API key from memory");
-                key.clone()
-            },
-            None => {
-                eprintln!("No API key in memory, trying keyring...");
-                // メモリにない場合はファイルから取得を試みる
-                if let Ok(key) = ApiKeyStorage::get_api_key() {
-                    eprintln!("Got API key from file storage");
-                    key
-                } else if let Ok(key) = ApiKeyManager::get_api_key() {
-                    eprintln!("Got API key from keyring");
-                    // ファイルにも保存しておく
-                    let _ = ApiKeyStorage::save_api_key(&key);
-                    key
-                } else {
-                    eprintln!("Failed to get API key from any source");
-                    let _ = app.emit("audio-error", "APIキーが設定されていません".to_string());
-                    return Err("APIキーが設定されていません".to_string());
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("[TTS Command] Skipped {} playback events", skipped);
                 }
+                Err(broadcast::error::RecvError::Closed) => break,
             }
         }
-    };
-    
-    // バックグラウンドで音声合成を実行
-    let cancel_tx_clone = state.cancel_tx.clone();
-    tokio::spawn(async move {
-        eprintln!("Starting synthesis task...");
-        let client = CartesiaClient::new_with_api_key(config, api_key);
-        
-        if let Err(e) = client.synthesize_speech(&text, audio_tx, cancel_rx).await {
-            eprintln!("音声合成に失敗しました: {}", e);
-            let _ = app.emit("audio-error", format!("音声合成に失敗しました: {}", e));
-        } else {
-            eprintln!("Synthesis completed successfully");
-        }
-        
-        // キャンセルチャンネルをクリア
-        let mut cancel_sender = cancel_tx_clone.lock().await;
-        *cancel_sender = None;
     });
     
-    info!("[TTS Command] Command returned successfully");
     Ok(())
 }
 
-#[tauri::command]
-pub async fn stop_speech(state: State<'_, TTSState>) -> Result<(), String> {
-    eprintln!("stop_speech command called");
-    
-    // キャンセル信号を送信
-    let mut cancel_sender = state.cancel_tx.lock().await;
-    if let Some(sender) = cancel_sender.take() {
-        let _ = sender.send(());
-        eprintln!("Cancel signal sent");
+/// APIキーをメモリ・ファイル・keyringの順に探す
+pub async fn resolve_api_key(state: &TTSState) -> Result<String, String> {
+    let stored_key = state.api_key.lock().await;
+    log::debug!("[TTS Command] Checking stored API key: {:?}", stored_key.is_some());
+    match stored_key.as_ref() {
+        Some(key) => {
+            log::debug!("[TTS Command] Using API key from memory");
+            Ok(key.clone())
+        },
+        None => {
+            log::debug!("[TTS Command] No API key in memory, trying keyring...");
+            // メモリにない場合はファイルから取得を試みる
+            if let Ok(key) = ApiKeyStorage::get_api_key() {
+                log::debug!("[TTS Command] Got API key from file storage");
+                Ok(key)
+            } else if let Ok(key) = ApiKeyManager::get_api_key() {
+                log::debug!("[TTS Command] Got API key from keyring");
+                // ファイルにも保存しておく
+                let _ = ApiKeyStorage::save_api_key(&key);
+                Ok(key)
+            } else {
+                log::debug!("[TTS Command] Failed to get API key from any source");
+                Err("APIキーが設定されていません".to_string())
+            }
+        }
     }
-    
-    // 音声再生を停止
-    let mut audio_manager = state.audio_manager.lock().await;
-    if let Some(manager) = audio_manager.as_ref() {
-        let _ = manager.stop();
-        eprintln!("Audio playback stopped");
+}
+
+/// 再生と同じパイプラインで合成し、指定形式にエンコードする
+pub async fn synthesize_encoded(
+    state: &TTSState,
+    config: TTSConfig,
+    text: &str,
+    format: ExportFormat,
+    effects: Option<Vec<EffectConfig>>,
+) -> Result<Vec<u8>, String> {
+    if text.trim().is_empty() {
+        return Err("テキストが空です".to_string());
     }
-    *audio_manager = None;
+    let api_key = resolve_api_key(state).await?;
+    let audio_config = state.audio_config.lock().await.clone();
+    let effects = effects.unwrap_or_else(|| config.effects_for_voice());
     
-    let mut is_synthesizing = state.is_synthesizing.lock().await;
-    *is_synthesizing = false;
-    Ok(())
-}
\ No newline at end of file
+    let samples = render_speech(config, api_key, text, &effects, &audio_config)
+        .await
+        .map_err(|e| format!("音声合成に失敗しました: {}", e))?;
+    export::encode(&samples, INPUT_SAMPLE_RATE, format)
+        .map_err(|e| format!("音声のエンコードに失敗しました: {}", e))
+}
@@ -0,0 +1,119 @@
+/**
+ * HTTPサーバーのアクセス制限
+ * 許可していないオリジン・ほかのサイトからのアクセスを断り、設定に応じてアクセストークンを確認する
+ */
+
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::Arc;
+use tauri::Manager;
+
+use super::config::HttpServerConfig;
+use super::handlers::AppState;
+use crate::commands::http::HttpServerState;
+
+#[derive(Debug, Deserialize)]
+struct TokenParams {
+    token: Option<String>,
+}
+
+fn reject(status: StatusCode, message: &str) -> Response {
+    (status, Json(json!({ "success": false, "message": message }))).into_response()
+}
+
+/// `Authorization: Bearer <トークン>` か `?token=<トークン>` で渡されたトークン
+fn request_token(request: &Request) -> Option<String> {
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string());
+    bearer.or_else(|| {
+        Query::<TokenParams>::try_from_uri(request.uri())
+            .ok()
+            .and_then(|Query(params)| params.token)
+    })
+}
+
+/// 長さ以外の情報を比較時間から漏らさずに比べる
+fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// ブラウザからのアクセスで、許可していないオリジンからのものならそのオリジンを返す
+/// ブラウザからのアクセスはオリジンが付く（WebSocketはCORSの対象外のため、ここで断る）
+/// <img> などの埋め込みやリンクからのGETはオリジンが付かないため、Sec-Fetch-Site でほかのサイトからと見分ける
+fn rejected_origin<'a>(config: &HttpServerConfig, headers: &'a HeaderMap) -> Option<&'a str> {
+    let origin = headers.get(header::ORIGIN).map(|origin| origin.to_str().unwrap_or_default());
+    let from_other_site = headers
+        .get("sec-fetch-site")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|site| site.eq_ignore_ascii_case("cross-site") || site.eq_ignore_ascii_case("same-site"));
+    if origin.is_none() && !from_other_site {
+        return None;
+    }
+    let origin = origin.unwrap_or_default();
+    (!config.allows_origin(origin)).then_some(origin)
+}
+
+/// オリジンとアクセストークンを確認する（/health 以外の全てのエンドポイントに掛ける）
+pub async fn authorize(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let app_handle = state.app_handle.lock().await.clone();
+    let http_state = app_handle.state::<HttpServerState>();
+    let config = http_state.config.read().await.clone();
+
+    if let Some(origin) = rejected_origin(&config, request.headers()) {
+        log::warn!("Rejected request from origin {:?} to {}", origin, request.uri().path());
+        return reject(StatusCode::FORBIDDEN, "許可されていないオリジンからのアクセスです");
+    }
+
+    if config.require_token {
+        let authorized = match (request_token(&request), http_state.token()) {
+            (Some(given), Some(expected)) => token_matches(&given, &expected),
+            _ => false,
+        };
+        if !authorized {
+            log::warn!("Rejected request without a valid token to {}", request.uri().path());
+            return reject(StatusCode::UNAUTHORIZED, "アクセストークンが正しくありません");
+        }
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_origin_check() {
+        let extension = "chrome-extension://abcdefghijklmnopabcdefghijklmnop";
+        let config = HttpServerConfig { allowed_origins: vec![extension.to_string()], ..HttpServerConfig::default() };
+        assert_eq!(rejected_origin(&config, &headers(&[("origin", extension)])), None);
+        assert_eq!(
+            rejected_origin(&config, &headers(&[("origin", "https://evil.example.com")])),
+            Some("https://evil.example.com")
+        );
+        // ほかのサイトに埋め込まれた <img> などはオリジンが無くても断る
+        assert_eq!(rejected_origin(&config, &headers(&[("sec-fetch-site", "cross-site")])), Some(""));
+        // ブラウザ以外（curlなど）や、アドレスバーから開いたページは通す
+        assert_eq!(rejected_origin(&config, &headers(&[])), None);
+        assert_eq!(rejected_origin(&config, &headers(&[("sec-fetch-site", "none")])), None);
+    }
+}
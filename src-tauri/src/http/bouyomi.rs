@@ -0,0 +1,126 @@
+/**
+ * 棒読みちゃん互換のHTTPエンドポイント
+ * 棒読みちゃん向けのブラウザ拡張・スクリプトから GET /talk などで読み上げ・操作を受け付ける
+ */
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use super::handlers::AppState;
+use crate::bouyomi::protocol::Talk;
+use crate::bouyomi::talk_to_queue;
+use crate::commands::tts::{clear_queue, set_paused, skip_lane, TTSState};
+use crate::queue::Admission;
+
+fn default_value() -> i16 {
+    -1
+}
+
+/// 棒読みちゃんの /talk のクエリ（速度・音程・音量は -1 で既定の設定）
+#[derive(Debug, Deserialize)]
+pub struct TalkParams {
+    pub text: String,
+    #[serde(default)]
+    pub voice: i16,
+    #[serde(default = "default_value")]
+    pub volume: i16,
+    #[serde(default = "default_value")]
+    pub speed: i16,
+    #[serde(default = "default_value")]
+    pub tone: i16,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TalkResponse {
+    /// 積んだ読み上げの通し番号（除外・破棄した場合は含めない）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<u64>,
+}
+
+async fn app_handle(state: &AppState) -> AppHandle {
+    state.app_handle.lock().await.clone()
+}
+
+/// 読み上げる
+pub async fn handle_talk(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<TalkParams>,
+) -> Response {
+    if params.text.trim().is_empty() {
+        return (StatusCode::BAD_REQUEST, Json(json!({}))).into_response();
+    }
+    let talk = Talk {
+        speed: params.speed,
+        tone: params.tone,
+        volume: params.volume,
+        voice: params.voice,
+        text: params.text,
+    };
+    let app_handle = app_handle(&state).await;
+    let task_id = match talk_to_queue(app_handle.state::<TTSState>().inner(), &talk) {
+        Admission::Queued(item) | Admission::Merged(item) => Some(item.sequence),
+        Admission::Filtered(_) | Admission::Overflowed => None,
+    };
+    Json(TalkResponse { task_id }).into_response()
+}
+
+/// 一時停止する
+pub async fn handle_pause(State(state): State<Arc<AppState>>) -> Response {
+    let app_handle = app_handle(&state).await;
+    match set_paused(app_handle.state::<TTSState>().inner(), true).await {
+        Ok(()) => Json(json!({})).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+/// 再開する
+pub async fn handle_resume(State(state): State<Arc<AppState>>) -> Response {
+    let app_handle = app_handle(&state).await;
+    match set_paused(app_handle.state::<TTSState>().inner(), false).await {
+        Ok(()) => Json(json!({})).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+/// 先頭のレーンの読み上げをスキップする
+pub async fn handle_skip(State(state): State<Arc<AppState>>) -> Response {
+    let app_handle = app_handle(&state).await;
+    match skip_lane(app_handle.state::<TTSState>().inner(), None).await {
+        Ok(()) => Json(json!({})).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e).into_response(),
+    }
+}
+
+/// 待機中の読み上げを全て取り除く
+pub async fn handle_clear(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let app_handle = app_handle(&state).await;
+    clear_queue(app_handle.state::<TTSState>().inner()).await;
+    Json(json!({}))
+}
+
+/// 一時停止中か
+pub async fn handle_get_pause(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let app_handle = app_handle(&state).await;
+    Json(json!({ "pause": app_handle.state::<TTSState>().queue.is_paused() }))
+}
+
+/// 読み上げ中か
+pub async fn handle_get_now_playing(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let app_handle = app_handle(&state).await;
+    Json(json!({ "nowPlaying": app_handle.state::<TTSState>().queue.is_speaking() }))
+}
+
+/// 待機中の読み上げの件数
+pub async fn handle_get_talk_task_count(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let app_handle = app_handle(&state).await;
+    let count = app_handle.state::<TTSState>().queue.snapshot().pending.len();
+    Json(json!({ "talkTaskCount": count }))
+}
@@ -1,16 +1,26 @@
 /**
  * HTTPサーバー設定管理
- * ポート番号の設定と永続化を管理
+ * ポート番号・アクセストークン・許可するオリジンの設定を管理
  */
 
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::queue::persist::write_atomic;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HttpServerConfig {
     pub port: u16,
     pub enabled: bool,
+    /// アクセストークンを必須にするか（/health は常に公開）
+    #[serde(default)]
+    pub require_token: bool,
+    /// ブラウザからのアクセスを許可するオリジン（"*" で全て許可）
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
 }
 
 impl Default for HttpServerConfig {
@@ -18,6 +28,8 @@ impl Default for HttpServerConfig {
         Self {
             port: 50080,
             enabled: true,
+            require_token: false,
+            allowed_origins: Vec::new(),
         }
     }
 }
@@ -27,7 +39,30 @@ pub type SharedConfig = Arc<RwLock<HttpServerConfig>>;
 impl HttpServerConfig {
     #[allow(dead_code)]
     pub fn new(port: u16, enabled: bool) -> Self {
-        Self { port, enabled }
+        Self { port, enabled, ..Self::default() }
+    }
+
+    /// 保存された設定を読み込む（無い・壊れている場合は既定値）
+    pub fn load(path: &Path) -> Self {
+        let Ok(bytes) = std::fs::read(path) else {
+            return Self::default();
+        };
+        let config = serde_json::from_slice::<Self>(&bytes)
+            .map_err(|e| e.to_string())
+            .and_then(|config| {
+                Self::validate_port(config.port)?;
+                Self::validate_origins(&config.allowed_origins)?;
+                Ok(config)
+            });
+        config.unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid HTTP server config {:?}: {}", path, e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let bytes = serde_json::to_vec_pretty(self).context("HTTPサーバー設定のシリアライズに失敗しました")?;
+        write_atomic(path, &bytes)
     }
 
     pub fn validate_port(port: u16) -> Result<(), String> {
@@ -37,4 +72,51 @@ impl HttpServerConfig {
             Ok(())
         }
     }
-}
\ No newline at end of file
+
+    /// オリジンは "*"・"null"・"https://example.com" や "chrome-extension://<ID>" の形式（末尾の / は付けない）
+    pub fn validate_origins(origins: &[String]) -> Result<(), String> {
+        for origin in origins {
+            let scheme_and_host = origin.split_once("://").is_some_and(|(scheme, host)| {
+                scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+                    && !host.is_empty()
+                    && !host.contains('/')
+            });
+            let valid = origin == "*"
+                || origin == "null"
+                || (scheme_and_host && origin.chars().all(|c| c.is_ascii_graphic()));
+            if !valid {
+                return Err(format!("オリジンの形式が不正です: {}", origin));
+            }
+        }
+        Ok(())
+    }
+
+    /// ブラウザからのアクセスを許可するオリジンか
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        self.allowed_origins
+            .iter()
+            .any(|allowed| allowed == "*" || allowed.eq_ignore_ascii_case(origin))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_origin_allowlist() {
+        let config = HttpServerConfig {
+            allowed_origins: vec!["https://obs.example.com".to_string()],
+            ..HttpServerConfig::default()
+        };
+        assert!(config.allows_origin("https://OBS.example.com"));
+        assert!(!config.allows_origin("https://evil.example.com"));
+        assert!(!HttpServerConfig::default().allows_origin("https://obs.example.com"));
+
+        assert!(HttpServerConfig::validate_origins(&["*".to_string(), "http://localhost:3000".to_string()]).is_ok());
+        assert!(HttpServerConfig::validate_origins(&["https://example.com/".to_string()]).is_err());
+        assert!(HttpServerConfig::validate_origins(&["example.com".to_string()]).is_err());
+        assert!(HttpServerConfig::validate_origins(&["moz-extension://0f1e2d3c-4b5a".to_string()]).is_ok());
+    }
+}
@@ -4,15 +4,22 @@
  */
 
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::{IntoResponse, Json},
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::Mutex;
 
+use crate::audio::effects::EffectConfig;
+use crate::audio::export::ExportFormat;
+use crate::commands::tts::{synthesize_encoded, TTSState};
+use crate::queue::filter::FilterReason;
+use crate::queue::{Admission, Priority, Source, SpeechRequest};
+use crate::tts::storage::app_data_dir;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TtsRequest {
     pub text: String,
@@ -22,12 +29,61 @@ pub struct TtsRequest {
     pub voice_speed: Option<f32>,
     #[serde(default)]
     pub language: Option<String>,
+    #[serde(default)]
+    pub effects: Option<Vec<EffectConfig>>,
+    /// 送信者（チャットのユーザー名など, 連投の判定に使う）
+    #[serde(default)]
+    pub sender: Option<String>,
+    /// 読み上げるレーン（未指定なら既定のレーン）
+    #[serde(default)]
+    pub lane: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct TtsResponse {
     pub success: bool,
     pub message: String,
+    /// 積んだ読み上げのID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// 連投・荒らし対策で除外・統合した理由
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filtered: Option<FilterReason>,
+}
+
+/// フロントエンドへ通知するHTTP読み上げリクエスト
+#[derive(Debug, Serialize)]
+struct TtsRequestNotice<'a> {
+    id: &'a str,
+    #[serde(flatten)]
+    request: &'a TtsRequest,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SynthesizeRequest {
+    pub text: String,
+    #[serde(default)]
+    pub format: ExportFormat,
+    /// 再生速度（0.5〜3.0, ピッチを保ったまま変換）
+    #[serde(default)]
+    pub speed: Option<f32>,
+    #[serde(default)]
+    pub voice_speed: Option<f32>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub effects: Option<Vec<EffectConfig>>,
+    /// 指定した場合は書き出し先ディレクトリに保存し、ボディには音声を含めない
+    #[serde(default)]
+    pub file_name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SynthesizeResponse {
+    pub success: bool,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -45,43 +101,197 @@ pub async fn handle_tts(
     State(state): State<Arc<AppState>>,
     Json(payload): Json<TtsRequest>,
 ) -> impl IntoResponse {
+    let app_handle = state.app_handle.lock().await.clone();
+    let (status, response) = submit_tts(&app_handle, &payload);
+    (status, Json(response))
+}
+
+/// 読み上げ要求をキューに積む（HTTPとWebSocketで共通）
+pub fn submit_tts(app_handle: &AppHandle, payload: &TtsRequest) -> (StatusCode, TtsResponse) {
     if payload.text.trim().is_empty() {
         return (
             StatusCode::BAD_REQUEST,
-            Json(TtsResponse {
+            TtsResponse {
                 success: false,
                 message: "テキストが空です".to_string(),
-            }),
+                id: None,
+                filtered: None,
+            },
         );
     }
 
-    // Tauriアプリケーションに読み上げイベントを送信
-    let app_handle = state.app_handle.lock().await;
-    
-    log::info!("Sending http-tts-request event with payload: {:?}", payload);
-    
-    // すべてのウィンドウにイベントを送信
-    match app_handle.emit("http-tts-request", &payload) {
-        Ok(_) => {
-            log::info!("Successfully emitted http-tts-request event");
-            (
-            StatusCode::OK,
-            Json(TtsResponse {
-                success: true,
-                message: "読み上げリクエストを受け付けました".to_string(),
-            }),
-        )
+    let queue = &app_handle.state::<TTSState>().queue;
+    if let Err(message) = queue.check_lane(payload.lane.as_deref()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            TtsResponse {
+                success: false,
+                message,
+                id: None,
+                filtered: None,
+            },
+        );
+    }
+    let admission = queue.enqueue(SpeechRequest {
+        source: Source::Http,
+        sender: payload.sender.clone(),
+        priority: Priority::parse(payload.priority.as_deref()),
+        lane: payload.lane.clone(),
+        language: payload.language.clone(),
+        voice_speed: payload.voice_speed,
+        effects: payload.effects.clone(),
+        ..SpeechRequest::new(payload.text.clone())
+    });
+    let (success, message, id, filtered) = match admission {
+        Admission::Queued(item) => {
+            log::info!("Enqueued HTTP TTS request {} ({:?})", item.id, item.request.priority);
+            // フロントエンドの履歴に表示するため、読み上げIDを添えて通知する
+            let notice = TtsRequestNotice { id: &item.id, request: payload };
+            if let Err(e) = app_handle.emit("http-tts-request", &notice) {
+                log::error!("Failed to emit http-tts-request event: {}", e);
+            }
+            (true, "読み上げリクエストを受け付けました", Some(item.id), None)
+        }
+        Admission::Merged(item) => (
+            true,
+            "直前の読み上げにまとめました",
+            Some(item.id),
+            Some(FilterReason::Merged),
+        ),
+        Admission::Filtered(reason) => {
+            let message = match reason {
+                FilterReason::Throttled => "連投のため読み上げませんでした",
+                _ => "重複のため読み上げませんでした",
+            };
+            (false, message, None, Some(reason))
+        }
+        Admission::Overflowed => (false, "キューがいっぱいのため読み上げませんでした", None, None),
+    };
+
+    (
+        StatusCode::OK,
+        TtsResponse {
+            success,
+            message: message.to_string(),
+            id,
+            filtered,
         },
-        Err(e) => {
-            log::error!("Failed to emit http-tts-request event: {}", e);
-            (
-            StatusCode::INTERNAL_SERVER_ERROR,
+    )
+}
+
+/// 読み上げの進み具合（積まれてから読み上げ終わるまでの状態と時刻）
+pub async fn handle_tts_status(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Response {
+    let app_handle = state.app_handle.lock().await.clone();
+    match app_handle.state::<TTSState>().queue.tracker().get(&id) {
+        Some(status) => Json(status).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
             Json(TtsResponse {
                 success: false,
-                message: format!("エラーが発生しました: {}", e),
+                message: "読み上げが見つかりません".to_string(),
+                id: None,
+                filtered: None,
             }),
         )
-        },
+            .into_response(),
+    }
+}
+
+fn synthesize_error(status: StatusCode, message: String) -> Response {
+    (
+        status,
+        Json(SynthesizeResponse {
+            success: false,
+            message,
+            path: None,
+        }),
+    )
+        .into_response()
+}
+
+/// 音声ファイル書き出しエンドポイント（再生はしない）
+pub async fn handle_synthesize(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<SynthesizeRequest>,
+) -> Response {
+    if payload.text.trim().is_empty() {
+        return synthesize_error(StatusCode::BAD_REQUEST, "テキストが空です".to_string());
+    }
+
+    // ファイル名にパス区切りを含めさせない
+    if let Some(file_name) = payload.file_name.as_deref() {
+        if file_name.is_empty() || file_name.contains(['/', '\\']) || file_name.starts_with('.') {
+            return synthesize_error(StatusCode::BAD_REQUEST, "ファイル名が不正です".to_string());
+        }
+    }
+
+    let app_handle = state.app_handle.lock().await.clone();
+    let tts_state = app_handle.state::<TTSState>();
+
+    let mut config = tts_state.config.lock().await.clone();
+    if let Some(language) = payload.language.as_deref() {
+        config = config.with_language(language);
+    }
+    if let Some(voice_speed) = payload.voice_speed {
+        config = config.with_voice_speed(voice_speed);
+    }
+    if let Some(speed) = payload.speed {
+        config = config.with_speed(speed);
+    }
+
+    log::info!("Synthesizing to {:?} ({} chars)", payload.format, payload.text.chars().count());
+    let bytes = match synthesize_encoded(
+        tts_state.inner(),
+        config,
+        &payload.text,
+        payload.format,
+        payload.effects,
+    )
+    .await
+    {
+        Ok(bytes) => bytes,
+        Err(e) => return synthesize_error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    };
+
+    match payload.file_name {
+        Some(file_name) => {
+            let export_dir = match app_data_dir() {
+                Ok(dir) => dir.join("exports"),
+                Err(e) => return synthesize_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+            };
+            let mut path = export_dir.join(file_name);
+            if path.extension().is_none() {
+                path.set_extension(payload.format.extension());
+            }
+            let write_result = match tokio::fs::create_dir_all(&export_dir).await {
+                Ok(()) => tokio::fs::write(&path, &bytes).await,
+                Err(e) => Err(e),
+            };
+            match write_result {
+                Ok(()) => (
+                    StatusCode::OK,
+                    Json(SynthesizeResponse {
+                        success: true,
+                        message: format!("{}バイトを書き出しました", bytes.len()),
+                        path: Some(path.to_string_lossy().to_string()),
+                    }),
+                )
+                    .into_response(),
+                Err(e) => synthesize_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("ファイルの書き込みに失敗しました: {}", e),
+                ),
+            }
+        }
+        None => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, payload.format.mime_type())],
+            bytes,
+        )
+            .into_response(),
     }
 }
 
@@ -4,8 +4,13 @@
  */
 
 pub mod server;
+pub mod auth;
+pub mod bouyomi;
 pub mod config;
 pub mod handlers;
+pub mod queue;
+pub mod schedules;
+pub mod websocket;
 
 pub use server::HttpServer;
 pub use config::HttpServerConfig;
\ No newline at end of file
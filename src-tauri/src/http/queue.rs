@@ -0,0 +1,127 @@
+/**
+ * 読み上げキューのHTTPエンドポイント
+ * キューの確認と、スキップ・一時停止・停止などの操作を行う（Tauriコマンドと同じ処理）
+ */
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+
+use super::handlers::AppState;
+use crate::commands::tts::{cancel_item, clear_queue, set_paused, skip_lane, stop_all, TTSState};
+
+#[derive(Debug, Serialize)]
+pub struct ControlResponse {
+    pub success: bool,
+    pub message: String,
+    /// 取り除いた読み上げの件数（クリア時のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cleared: Option<usize>,
+}
+
+impl ControlResponse {
+    fn ok(message: &str) -> (StatusCode, Json<Self>) {
+        (StatusCode::OK, Json(Self { success: true, message: message.to_string(), cleared: None }))
+    }
+
+    fn error(status: StatusCode, message: String) -> (StatusCode, Json<Self>) {
+        (status, Json(Self { success: false, message, cleared: None }))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SkipParams {
+    /// スキップするレーン（未指定なら既定のレーン）
+    #[serde(default)]
+    pub lane: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StopParams {
+    /// 単語の切れ目まで待ってから止めるか
+    #[serde(default)]
+    pub soft: bool,
+}
+
+async fn app_handle(state: &AppState) -> AppHandle {
+    state.app_handle.lock().await.clone()
+}
+
+/// 読み上げ中・待機中の読み上げ
+pub async fn handle_get_queue(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let app_handle = app_handle(&state).await;
+    let snapshot = app_handle.state::<TTSState>().queue.snapshot();
+    Json(snapshot)
+}
+
+/// 読み上げを1件取り消す（読み上げ中ならフェードアウトして止める）
+pub async fn handle_cancel_item(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let app_handle = app_handle(&state).await;
+    if cancel_item(app_handle.state::<TTSState>().inner(), &id).await {
+        ControlResponse::ok("読み上げを取り消しました")
+    } else {
+        ControlResponse::error(StatusCode::NOT_FOUND, "読み上げが見つかりません".to_string())
+    }
+}
+
+/// 待機中の読み上げを全て取り除く（読み上げ中の読み上げはそのまま）
+pub async fn handle_clear_queue(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let app_handle = app_handle(&state).await;
+    let cleared = clear_queue(app_handle.state::<TTSState>().inner()).await.len();
+    (
+        StatusCode::OK,
+        Json(ControlResponse {
+            success: true,
+            message: "待機中の読み上げを取り除きました".to_string(),
+            cleared: Some(cleared),
+        }),
+    )
+}
+
+/// レーンで再生中の読み上げをスキップする
+pub async fn handle_skip(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SkipParams>,
+) -> impl IntoResponse {
+    let app_handle = app_handle(&state).await;
+    match skip_lane(app_handle.state::<TTSState>().inner(), params.lane.as_deref()).await {
+        Ok(()) => ControlResponse::ok("読み上げをスキップしました"),
+        Err(e) => ControlResponse::error(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+/// 読み上げを一時停止する
+pub async fn handle_pause(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let app_handle = app_handle(&state).await;
+    match set_paused(app_handle.state::<TTSState>().inner(), true).await {
+        Ok(()) => ControlResponse::ok("読み上げを一時停止しました"),
+        Err(e) => ControlResponse::error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+/// 一時停止した読み上げを再開する
+pub async fn handle_resume(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let app_handle = app_handle(&state).await;
+    match set_paused(app_handle.state::<TTSState>().inner(), false).await {
+        Ok(()) => ControlResponse::ok("読み上げを再開しました"),
+        Err(e) => ControlResponse::error(StatusCode::INTERNAL_SERVER_ERROR, e),
+    }
+}
+
+/// 読み上げを停止し、待機中の読み上げも全て取り消す
+pub async fn handle_stop(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<StopParams>,
+) -> impl IntoResponse {
+    let app_handle = app_handle(&state).await;
+    stop_all(app_handle.state::<TTSState>().inner(), params.soft).await;
+    ControlResponse::ok("読み上げを停止しました")
+}
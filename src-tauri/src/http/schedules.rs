@@ -0,0 +1,98 @@
+/**
+ * 予約読み上げのHTTPエンドポイント
+ * 予約の一覧・追加・削除と予定表（.ics）の取り込みを行う
+ */
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json},
+};
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::Manager;
+
+use super::handlers::AppState;
+use crate::scheduler::{IcsImport, NewSchedule, ScheduledJob, Scheduler};
+
+#[derive(Debug, Serialize)]
+pub struct ScheduleResponse {
+    pub success: bool,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job: Option<ScheduledJob>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub import: Option<IcsImport>,
+}
+
+impl ScheduleResponse {
+    fn error(status: StatusCode, message: String) -> (StatusCode, Json<Self>) {
+        (status, Json(Self { success: false, message, job: None, import: None }))
+    }
+}
+
+async fn scheduler(state: &AppState) -> Scheduler {
+    let app_handle = state.app_handle.lock().await.clone();
+    let scheduler = app_handle.state::<Scheduler>();
+    scheduler.inner().clone()
+}
+
+/// 予約の一覧
+pub async fn handle_list_schedules(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    Json(scheduler(&state).await.list())
+}
+
+/// 読み上げを予約する
+pub async fn handle_add_schedule(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<NewSchedule>,
+) -> impl IntoResponse {
+    match scheduler(&state).await.add(payload) {
+        Ok(job) => (
+            StatusCode::OK,
+            Json(ScheduleResponse {
+                success: true,
+                message: "読み上げを予約しました".to_string(),
+                job: Some(job),
+                import: None,
+            }),
+        ),
+        Err(e) => ScheduleResponse::error(StatusCode::BAD_REQUEST, e),
+    }
+}
+
+/// 予約を取り消す
+pub async fn handle_remove_schedule(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if scheduler(&state).await.remove(&id) {
+        (
+            StatusCode::OK,
+            Json(ScheduleResponse {
+                success: true,
+                message: "予約を取り消しました".to_string(),
+                job: None,
+                import: None,
+            }),
+        )
+    } else {
+        ScheduleResponse::error(StatusCode::NOT_FOUND, "予約が見つかりません".to_string())
+    }
+}
+
+/// 予定表（.ics の内容をそのままボディに送る）のアラームを予約として取り込む
+pub async fn handle_import_ics(State(state): State<Arc<AppState>>, body: String) -> impl IntoResponse {
+    match scheduler(&state).await.import_ics(&body) {
+        Ok(import) => (
+            StatusCode::OK,
+            Json(ScheduleResponse {
+                success: true,
+                message: format!("{}件のアラームを取り込みました", import.imported),
+                job: None,
+                import: Some(import),
+            }),
+        ),
+        Err(e) => ScheduleResponse::error(StatusCode::BAD_REQUEST, e),
+    }
+}
@@ -4,17 +4,28 @@
  */
 
 use axum::{
-    routing::{get, post},
+    middleware,
+    routing::{delete, get, post},
     Router,
 };
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tauri::AppHandle;
 use tokio::sync::Mutex;
-use tower_http::cors::{Any, CorsLayer};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 
+use super::auth::authorize;
+use super::bouyomi;
 use super::config::SharedConfig;
-use super::handlers::{handle_health, handle_tts, AppState};
+use super::handlers::{handle_health, handle_synthesize, handle_tts, handle_tts_status, AppState};
+use super::queue::{
+    handle_cancel_item, handle_clear_queue, handle_get_queue, handle_pause, handle_resume,
+    handle_skip, handle_stop,
+};
+use super::schedules::{
+    handle_add_schedule, handle_import_ics, handle_list_schedules, handle_remove_schedule,
+};
+use super::websocket::{handle_events, handle_levels};
 
 pub struct HttpServer {
     config: SharedConfig,
@@ -44,14 +55,36 @@ impl HttpServer {
             app_handle: Arc::clone(&self.app_handle),
         });
 
+        // 許可していないオリジンからのリクエストは authorize で断る
         let cors = CorsLayer::new()
-            .allow_origin(Any)
+            .allow_origin(AllowOrigin::mirror_request())
             .allow_methods(Any)
             .allow_headers(Any);
 
         let app = Router::new()
-            .route("/health", get(handle_health))
             .route("/tts", post(handle_tts))
+            .route("/tts/:id", get(handle_tts_status))
+            .route("/synthesize", post(handle_synthesize))
+            .route("/levels", get(handle_levels))
+            .route("/ws", get(handle_events))
+            .route("/queue", get(handle_get_queue))
+            .route("/queue/clear", post(handle_clear_queue))
+            .route("/queue/:id", delete(handle_cancel_item))
+            .route("/skip", post(handle_skip).get(bouyomi::handle_skip))
+            .route("/pause", post(handle_pause).get(bouyomi::handle_pause))
+            .route("/resume", post(handle_resume).get(bouyomi::handle_resume))
+            .route("/stop", post(handle_stop))
+            // 棒読みちゃん互換（GET）
+            .route("/talk", get(bouyomi::handle_talk))
+            .route("/clear", get(bouyomi::handle_clear))
+            .route("/getpause", get(bouyomi::handle_get_pause))
+            .route("/getnowplaying", get(bouyomi::handle_get_now_playing))
+            .route("/gettalktaskcount", get(bouyomi::handle_get_talk_task_count))
+            .route("/schedules", get(handle_list_schedules).post(handle_add_schedule))
+            .route("/schedules/import", post(handle_import_ics))
+            .route("/schedules/:id", delete(handle_remove_schedule))
+            .route_layer(middleware::from_fn_with_state(Arc::clone(&state), authorize))
+            .route("/health", get(handle_health))
             .layer(cors)
             .with_state(state);
 
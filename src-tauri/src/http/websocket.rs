@@ -0,0 +1,236 @@
+/**
+ * WebSocketエンドポイント
+ * 再生中の音声の状態や読み上げの進み具合を外部アプリケーション（アバター表示・OBS・ボットなど）へ配信し、操作を受け付ける
+ */
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::Response,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+
+use crate::audio::meter::AudioLevel;
+use crate::commands::tts::{cancel_item, clear_queue, set_paused, skip_lane, stop_all, TTSState};
+use crate::queue::tracker::SpeechStatus;
+use crate::queue::QueueSnapshot;
+
+use super::handlers::{submit_tts, AppState, TtsRequest};
+
+/// 出力レベルを配信するWebSocket（`audio-level` イベントと同じJSON）
+pub async fn handle_levels(State(state): State<Arc<AppState>>, ws: WebSocketUpgrade) -> Response {
+    let app_handle = state.app_handle.lock().await.clone();
+    let levels = app_handle.state::<TTSState>().level_events.subscribe();
+    ws.on_upgrade(move |socket| stream_levels(socket, levels))
+}
+
+async fn stream_levels(mut socket: WebSocket, mut levels: broadcast::Receiver<AudioLevel>) {
+    log::info!("[WebSocket] Level client connected");
+    loop {
+        tokio::select! {
+            level = levels.recv() => match level {
+                Ok(level) => {
+                    let Ok(json) = serde_json::to_string(&level) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(json)).await.is_err() {
+                        break;
+                    }
+                }
+                // 遅れたクライアントは古いレベルを読み飛ばす
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    log::info!("[WebSocket] Level client disconnected");
+}
+
+fn default_levels() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventParams {
+    /// 出力レベルも配信するか
+    #[serde(default = "default_levels")]
+    pub levels: bool,
+}
+
+/// クライアントから届く操作（`action` で種類を指定する）
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum ControlMessage {
+    /// 読み上げる（`POST /tts` と同じ項目）
+    Speak(TtsRequest),
+    Skip {
+        #[serde(default)]
+        lane: Option<String>,
+    },
+    Pause,
+    Resume,
+    Stop {
+        #[serde(default)]
+        soft: bool,
+    },
+    Cancel {
+        id: String,
+    },
+    Clear,
+    GetQueue,
+}
+
+impl ControlMessage {
+    fn action(&self) -> &'static str {
+        match self {
+            ControlMessage::Speak(_) => "speak",
+            ControlMessage::Skip { .. } => "skip",
+            ControlMessage::Pause => "pause",
+            ControlMessage::Resume => "resume",
+            ControlMessage::Stop { .. } => "stop",
+            ControlMessage::Cancel { .. } => "cancel",
+            ControlMessage::Clear => "clear",
+            ControlMessage::GetQueue => "get_queue",
+        }
+    }
+}
+
+/// クライアントへ送るメッセージ（`type` で種類を表す）
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum EventMessage {
+    /// 読み上げの状態の変化（`speech-status` イベントと同じ内容）
+    Speech(SpeechStatus),
+    /// 出力レベル（`audio-level` イベントと同じ内容）
+    Level(AudioLevel),
+    /// キューの内容（`get_queue` の応答）
+    Queue(QueueSnapshot),
+    /// 操作の結果
+    Result {
+        action: &'static str,
+        success: bool,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+    },
+}
+
+impl EventMessage {
+    fn result(action: &'static str, outcome: Result<&str, String>) -> Self {
+        let (success, message) = match outcome {
+            Ok(message) => (true, message.to_string()),
+            Err(message) => (false, message),
+        };
+        EventMessage::Result { action, success, message, id: None }
+    }
+}
+
+/// 読み上げの進み具合と出力レベルを配信し、操作を受け付けるWebSocket
+pub async fn handle_events(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<EventParams>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let app_handle = state.app_handle.lock().await.clone();
+    ws.on_upgrade(move |socket| stream_events(socket, app_handle, params.levels))
+}
+
+async fn stream_events(mut socket: WebSocket, app: AppHandle, levels_enabled: bool) {
+    log::info!("[WebSocket] Event client connected");
+    let state = app.state::<TTSState>();
+    let mut statuses = state.queue.tracker().subscribe();
+    let mut levels = state.level_events.subscribe();
+
+    loop {
+        let event = tokio::select! {
+            status = statuses.recv() => match status {
+                Ok(status) => EventMessage::Speech(status),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("[WebSocket] Event client skipped {} speech events", skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            level = levels.recv(), if levels_enabled => match level {
+                Ok(level) => EventMessage::Level(level),
+                // 遅れたクライアントは古いレベルを読み飛ばす
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => control(&app, &text).await,
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+        let Ok(json) = serde_json::to_string(&event) else {
+            continue;
+        };
+        if socket.send(Message::Text(json)).await.is_err() {
+            break;
+        }
+    }
+    log::info!("[WebSocket] Event client disconnected");
+}
+
+/// クライアントからの操作を実行する（HTTPのエンドポイントと同じ処理）
+async fn control(app: &AppHandle, text: &str) -> EventMessage {
+    let message = match serde_json::from_str::<ControlMessage>(text) {
+        Ok(message) => message,
+        Err(e) => return EventMessage::result("unknown", Err(format!("操作を解釈できません: {}", e))),
+    };
+    let action = message.action();
+    let state = app.state::<TTSState>();
+    let state = state.inner();
+
+    match message {
+        ControlMessage::Speak(request) => {
+            let (_, response) = submit_tts(app, &request);
+            EventMessage::Result {
+                action,
+                success: response.success,
+                message: response.message,
+                id: response.id,
+            }
+        }
+        ControlMessage::Skip { lane } => EventMessage::result(
+            action,
+            skip_lane(state, lane.as_deref()).await.map(|()| "読み上げをスキップしました"),
+        ),
+        ControlMessage::Pause => EventMessage::result(
+            action,
+            set_paused(state, true).await.map(|()| "読み上げを一時停止しました"),
+        ),
+        ControlMessage::Resume => EventMessage::result(
+            action,
+            set_paused(state, false).await.map(|()| "読み上げを再開しました"),
+        ),
+        ControlMessage::Stop { soft } => {
+            stop_all(state, soft).await;
+            EventMessage::result(action, Ok("読み上げを停止しました"))
+        }
+        ControlMessage::Cancel { id } => {
+            let cancelled = cancel_item(state, &id).await;
+            EventMessage::Result {
+                action,
+                success: cancelled,
+                message: if cancelled { "読み上げを取り消しました" } else { "読み上げが見つかりません" }.to_string(),
+                id: Some(id),
+            }
+        }
+        ControlMessage::Clear => {
+            clear_queue(state).await;
+            EventMessage::result(action, Ok("待機中の読み上げを取り除きました"))
+        }
+        ControlMessage::GetQueue => EventMessage::Queue(state.queue.snapshot()),
+    }
+}
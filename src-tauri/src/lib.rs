@@ -7,6 +7,9 @@ mod commands;
 mod tts;
 mod http;
 mod audio;
+mod bouyomi;
+mod queue;
+mod scheduler;
 
 use commands::tts::{
     TTSState, 
@@ -15,18 +18,42 @@ use commands::tts::{
     remove_api_key, 
     update_tts_config, 
     get_tts_config, 
+    set_voice_effects,
     synthesize_speech,
-    stop_speech
+    get_speech_queue,
+    get_speech_status,
+    synthesize_to_file,
+    stop_speech,
+    skip_speech,
+    pause_speech,
+    resume_speech,
+    cancel_speech,
+    clear_speech_queue
+};
+use commands::audio::{get_audio_config, get_audio_stats, update_audio_config};
+use commands::queue::{
+    get_queue_config,
+    get_queue_filter_report,
+    get_queue_restore_report,
+    update_queue_config
+};
+use commands::schedule::{
+    get_schedules,
+    add_schedule,
+    remove_schedule,
+    set_schedule_enabled,
+    import_ics_file
 };
 use commands::http::{
     HttpServerState,
     get_http_config,
     update_http_config,
-    start_http_server
+    start_http_server,
+    get_http_token,
+    regenerate_http_token
 };
 use commands::test::test_event_emit;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use tauri::Manager;
 
 
@@ -43,17 +70,41 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .manage(TTSState::default())
         .manage(HttpServerState::default())
+        .manage(scheduler::Scheduler::new())
         .invoke_handler(tauri::generate_handler![
             set_api_key,
             check_api_key,
             remove_api_key,
             update_tts_config,
             get_tts_config,
+            set_voice_effects,
             synthesize_speech,
+            get_speech_queue,
+            get_speech_status,
+            synthesize_to_file,
             stop_speech,
+            skip_speech,
+            pause_speech,
+            resume_speech,
+            cancel_speech,
+            clear_speech_queue,
+            get_audio_config,
+            get_audio_stats,
+            update_audio_config,
+            get_queue_config,
+            update_queue_config,
+            get_queue_restore_report,
+            get_queue_filter_report,
+            get_schedules,
+            add_schedule,
+            remove_schedule,
+            set_schedule_enabled,
+            import_ics_file,
             get_http_config,
             update_http_config,
             start_http_server,
+            get_http_token,
+            regenerate_http_token,
             test_event_emit
         ])
         .setup(|app| {
@@ -66,6 +117,33 @@ pub fn run() {
                 window.open_devtools();
             }
             
+            // 前回終了時に残っていた読み上げを復元してからワーカーを起動
+            match queue::QueueStore::open_default() {
+                Ok(store) => {
+                    let report = app.state::<TTSState>().queue.restore(store);
+                    if report.error.is_some() || !report.lost.is_empty() {
+                        log::warn!("[Main] Queue restore report: {:?}", report);
+                    }
+                }
+                Err(e) => log::error!("[Main] Queue persistence unavailable: {:#}", e),
+            }
+            queue::worker::spawn_worker(app.handle().clone());
+
+            // 保存された予約を読み込んでから予約読み上げを始める
+            match app.state::<scheduler::Scheduler>().restore_default() {
+                Ok(count) => log::info!("[Main] Restored {} schedules", count),
+                Err(e) => log::error!("[Main] Failed to restore schedules: {:#}", e),
+            }
+            scheduler::spawn_scheduler(app.handle().clone());
+            
+            // HTTPサーバーの設定とアクセストークンを読み込む（トークンは初回に生成する）
+            if let Err(e) = app.state::<HttpServerState>().load_config() {
+                log::error!("[Main] Failed to load HTTP server config: {}", e);
+            }
+            if let Err(e) = app.state::<HttpServerState>().load_token() {
+                log::error!("[Main] Failed to load HTTP access token: {}", e);
+            }
+
             // HTTPサーバーを自動起動
             let app_handle = app.handle().clone();
             
@@ -73,9 +151,8 @@ pub fn run() {
                 tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
                 log::info!("[Main] Starting HTTP server...");
                 
-                // HTTP server will be started via the default configuration
-                let http_config = http::HttpServerConfig::default();
-                let config = Arc::new(RwLock::new(http_config));
+                // 設定画面・アクセス制限と同じ設定を使う
+                let config = Arc::clone(&app_handle.state::<HttpServerState>().config);
                 
                 let server = http::HttpServer::new(
                     config,
@@ -86,9 +163,23 @@ pub fn run() {
                     log::error!("HTTPサーバーの起動に失敗しました: {}", e);
                 }
             });
+
+            // 棒読みちゃん互換サーバーを自動起動
+            let bouyomi = bouyomi::BouyomiServer::new(bouyomi::BouyomiConfig::default(), app.handle().clone());
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = bouyomi.start().await {
+                    log::error!("[Main] Bouyomi server stopped: {:#}", e);
+                }
+            });
             
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // 終了前に、まだ書き込んでいないキューの変更を保存する
+            if let tauri::RunEvent::Exit = event {
+                app.state::<TTSState>().queue.flush();
+            }
+        });
 }
\ No newline at end of file
@@ -0,0 +1,183 @@
+/**
+ * 読み上げの溜まりすぎへの対策
+ * キューの上限を超えた読み上げの間引きと、溜まり具合に応じた早口・短縮を担当する
+ */
+
+use serde::{Deserialize, Serialize};
+
+use super::item::QueueItem;
+
+/// 短縮した読み上げの末尾
+const TRUNCATED_SUFFIX: &str = "、以下略";
+
+/// キューの上限を超えたときの扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// 最も古い読み上げを捨てる
+    #[default]
+    DropOldest,
+    /// 最も優先度の低い読み上げ（同じ優先度では古いもの）を捨てる
+    DropLowest,
+    /// 古い読み上げを捨て、「ほか N 件」とだけ読み上げる
+    Summarize,
+}
+
+/// 溜まり具合に応じて早口・短縮して追いつく設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CatchUpConfig {
+    pub enabled: bool,
+    /// 待機中の読み上げがこの件数を超えたら追いつきはじめる
+    pub start_at: usize,
+    /// 待機中の読み上げがこの件数で最も早口にする
+    pub full_at: usize,
+    /// 最も早口にしたときの速度の倍率（通常の速度に掛ける）
+    pub max_speed: f32,
+    /// 追いついている間、この文字数を超える読み上げを短縮する（0で短縮しない）
+    pub truncate_chars: usize,
+}
+
+impl Default for CatchUpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            start_at: 3,
+            full_at: 10,
+            max_speed: 1.5,
+            truncate_chars: 80,
+        }
+    }
+}
+
+/// 溜まりすぎへの対策の設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BacklogConfig {
+    /// 待機中の読み上げの上限（0で無制限, 「ほか N 件」の読み上げは数えない）
+    pub max_length: usize,
+    pub overflow: OverflowPolicy,
+    pub catch_up: CatchUpConfig,
+}
+
+impl Default for BacklogConfig {
+    fn default() -> Self {
+        Self {
+            max_length: 50,
+            overflow: OverflowPolicy::default(),
+            catch_up: CatchUpConfig::default(),
+        }
+    }
+}
+
+impl BacklogConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        let catch_up = &self.catch_up;
+        if catch_up.full_at <= catch_up.start_at {
+            return Err("最も早口にする件数は追いつきはじめる件数より大きくしてください".to_string());
+        }
+        if !(1.0..=3.0).contains(&catch_up.max_speed) {
+            return Err("追いつくときの速度の倍率は1〜3の範囲で指定してください".to_string());
+        }
+        if catch_up.truncate_chars != 0 && catch_up.truncate_chars < 10 {
+            return Err("短縮する文字数は10文字以上で指定してください".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 読み上げ1件ぶんの追いつき方
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CatchUp {
+    /// 速度の倍率
+    pub speed: f32,
+    /// 短縮する文字数
+    pub max_chars: Option<usize>,
+}
+
+impl CatchUp {
+    pub const NORMAL: CatchUp = CatchUp { speed: 1.0, max_chars: None };
+
+    /// 待機中の件数から追いつき方を決める（件数に応じて倍率を徐々に上げる）
+    pub fn for_backlog(config: &CatchUpConfig, backlog: usize) -> Self {
+        if !config.enabled || backlog <= config.start_at {
+            return Self::NORMAL;
+        }
+        let range = config.full_at.saturating_sub(config.start_at).max(1);
+        let ratio = ((backlog - config.start_at) as f32 / range as f32).min(1.0);
+        Self {
+            speed: 1.0 + (config.max_speed - 1.0) * ratio,
+            max_chars: (config.truncate_chars > 0).then_some(config.truncate_chars),
+        }
+    }
+
+    /// 長すぎる読み上げを短縮する
+    pub fn truncate(&self, text: &str) -> String {
+        match self.max_chars {
+            Some(max_chars) if text.chars().count() > max_chars => {
+                let mut truncated: String = text.chars().take(max_chars).collect();
+                truncated.push_str(TRUNCATED_SUFFIX);
+                truncated
+            }
+            _ => text.to_string(),
+        }
+    }
+}
+
+/// 上限を超えたときに捨てる読み上げ
+/// 中断中の読み上げ（続きを再生待ち）と「ほか N 件」の読み上げは捨てない
+pub fn overflow_victim<'a>(
+    items: impl IntoIterator<Item = &'a QueueItem>,
+    policy: OverflowPolicy,
+) -> Option<&'a QueueItem> {
+    let candidates = items
+        .into_iter()
+        .filter(|item| !item.suspended && item.summarized == 0);
+    match policy {
+        OverflowPolicy::DropOldest | OverflowPolicy::Summarize => candidates.min_by_key(|item| item.sequence),
+        OverflowPolicy::DropLowest => candidates.min_by_key(|item| (item.request.priority, item.sequence)),
+    }
+}
+
+/// 捨てた読み上げの代わりに読み上げる文
+pub fn summary_text(count: usize) -> String {
+    format!("ほか{}件", count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::item::{Priority, SpeechRequest};
+
+    fn item(sequence: u64, priority: Priority) -> QueueItem {
+        QueueItem {
+            sequence,
+            ..QueueItem::for_test(&sequence.to_string(), SpeechRequest { priority, ..SpeechRequest::new("text") })
+        }
+    }
+
+    #[test]
+    fn test_overflow_victim_by_policy() {
+        let mut items = vec![item(0, Priority::High), item(1, Priority::Low), item(2, Priority::Normal)];
+        let victim = |items: &[QueueItem], policy| overflow_victim(items, policy).map(|item| item.sequence);
+        assert_eq!(victim(&items, OverflowPolicy::DropOldest), Some(0));
+        assert_eq!(victim(&items, OverflowPolicy::DropLowest), Some(1));
+        // 中断中の読み上げは捨てない
+        items[0].suspended = true;
+        assert_eq!(victim(&items, OverflowPolicy::Summarize), Some(1));
+    }
+
+    #[test]
+    fn test_catch_up_scales_with_backlog() {
+        let config = CatchUpConfig::default();
+        assert_eq!(CatchUp::for_backlog(&config, 3), CatchUp::NORMAL);
+        let halfway = CatchUp::for_backlog(&config, 6);
+        assert!(halfway.speed > 1.0 && halfway.speed < config.max_speed);
+        assert_eq!(CatchUp::for_backlog(&config, 100).speed, config.max_speed);
+
+        let long = "あ".repeat(100);
+        let truncated = halfway.truncate(&long);
+        assert_eq!(truncated.chars().count(), 80 + TRUNCATED_SUFFIX.chars().count());
+        assert_eq!(halfway.truncate("短い"), "短い");
+    }
+}
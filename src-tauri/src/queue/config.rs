@@ -0,0 +1,206 @@
+/**
+ * 読み上げキューの設定
+ * キューの保存・復元などの振る舞いを管理する
+ */
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::audio::playback::OVERLAY_LANE;
+use crate::tts::TTSConfig;
+
+use super::backlog::BacklogConfig;
+use super::filter::FilterConfig;
+use super::item::Priority;
+use super::prefetch::PrefetchConfig;
+use super::persist::write_atomic;
+
+/// キューの保存・復元の設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistenceConfig {
+    /// 待機中の読み上げをディスクに保存し、起動時に復元するか
+    pub enabled: bool,
+    /// 復元時、積まれてからこの秒数を過ぎた読み上げは破棄する（0で無制限）
+    pub max_age_secs: u64,
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_age_secs: 600,
+        }
+    }
+}
+
+/// 優先度の高い読み上げが届いたときの、読み上げ中の読み上げの扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreemptPolicy {
+    /// 読み上げ終わるのを待つ
+    #[default]
+    Wait,
+    /// 中断し、割り込んだ読み上げの後に続きから読み上げる
+    InterruptResume,
+    /// 中断して破棄する
+    InterruptDrop,
+    /// 音量を下げて重ねて読み上げる
+    Overlay,
+}
+
+/// 割り込みの設定（届いた読み上げの優先度ごと）
+/// 読み上げ中の読み上げより優先度が高い場合にだけ適用する（low が割り込むことはない）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreemptionConfig {
+    pub normal: PreemptPolicy,
+    pub high: PreemptPolicy,
+}
+
+impl PreemptionConfig {
+    pub fn policy_for(&self, priority: Priority) -> PreemptPolicy {
+        match priority {
+            Priority::Low => PreemptPolicy::Wait,
+            Priority::Normal => self.normal,
+            Priority::High => self.high,
+        }
+    }
+}
+
+fn default_lane_volume() -> f32 {
+    1.0
+}
+
+/// 再生レーン
+/// レーンごとに別々のキューで1件ずつ読み上げ、ほかのレーンの読み上げと重ねて再生する
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaneConfig {
+    pub name: String,
+    /// 声・言語・話速の既定値（読み上げ要求で指定されればそちらを使う）
+    #[serde(default)]
+    pub voice_id: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub voice_speed: Option<f32>,
+    /// 音量（0.0〜1.0, 全体の音量に掛ける）
+    #[serde(default = "default_lane_volume")]
+    pub volume: f32,
+}
+
+impl LaneConfig {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            voice_id: None,
+            language: None,
+            voice_speed: None,
+            volume: default_lane_volume(),
+        }
+    }
+
+    /// レーンの既定値をTTS設定に反映する
+    pub fn apply_to(&self, mut config: TTSConfig) -> TTSConfig {
+        if let Some(language) = self.language.as_deref() {
+            config = config.with_language(language);
+        }
+        if let Some(voice_id) = self.voice_id.clone() {
+            config = config.with_voice_id(voice_id);
+        }
+        if let Some(voice_speed) = self.voice_speed {
+            config = config.with_voice_speed(voice_speed);
+        }
+        config
+    }
+}
+
+/// 読み上げキューの設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueConfig {
+    pub persistence: PersistenceConfig,
+    pub preemption: PreemptionConfig,
+    /// 連投・荒らし対策
+    pub filter: FilterConfig,
+    /// 溜まりすぎへの対策（レーンごと）
+    pub backlog: BacklogConfig,
+    /// 次の読み上げの先読み（レーンごと）
+    pub prefetch: PrefetchConfig,
+    /// 再生レーン（先頭がレーンを指定しない読み上げのレーン）
+    pub lanes: Vec<LaneConfig>,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            persistence: PersistenceConfig::default(),
+            preemption: PreemptionConfig::default(),
+            filter: FilterConfig::default(),
+            backlog: BacklogConfig::default(),
+            prefetch: PrefetchConfig::default(),
+            lanes: ["chat", "alerts", "system"].into_iter().map(LaneConfig::new).collect(),
+        }
+    }
+}
+
+impl QueueConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        // 1週間を超える保持は想定しない
+        if self.persistence.max_age_secs > 7 * 24 * 60 * 60 {
+            return Err("復元する読み上げの保持期間は1週間以内で指定してください".to_string());
+        }
+        if self.lanes.is_empty() {
+            return Err("レーンを1つ以上指定してください".to_string());
+        }
+        let mut names = HashSet::new();
+        for lane in &self.lanes {
+            let valid = !lane.name.is_empty()
+                && lane.name.len() <= 32
+                && lane.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid || lane.name == OVERLAY_LANE {
+                return Err(format!("レーン名が不正です: {}（英数字・-・_ の32文字以内）", lane.name));
+            }
+            if !names.insert(lane.name.as_str()) {
+                return Err(format!("レーン名が重複しています: {}", lane.name));
+            }
+            if !(0.0..=1.0).contains(&lane.volume) {
+                return Err(format!("レーン {} の音量は0.0〜1.0で指定してください", lane.name));
+            }
+        }
+        self.filter.validate()?;
+        self.backlog.validate()?;
+        self.prefetch.validate()
+    }
+
+    /// 名前でレーンを探す
+    pub fn lane(&self, name: &str) -> Option<&LaneConfig> {
+        self.lanes.iter().find(|lane| lane.name == name)
+    }
+
+    /// 読み上げるレーン（未指定・設定から消えたレーンは先頭のレーン）
+    pub fn resolve_lane(&self, name: Option<&str>) -> Option<&LaneConfig> {
+        name.and_then(|name| self.lane(name)).or(self.lanes.first())
+    }
+
+    /// 保存された設定を読み込む（無い・壊れている場合は既定値）
+    pub fn load(path: &Path) -> Self {
+        let Ok(bytes) = std::fs::read(path) else {
+            return Self::default();
+        };
+        let config = serde_json::from_slice::<Self>(&bytes)
+            .map_err(|e| e.to_string())
+            .and_then(|config| config.validate().map(|()| config));
+        config.unwrap_or_else(|e| {
+            log::warn!("[SpeechQueue] Ignoring invalid queue config {:?}: {}", path, e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let bytes = serde_json::to_vec_pretty(self).context("キュー設定のシリアライズに失敗しました")?;
+        write_atomic(path, &bytes)
+    }
+}
@@ -0,0 +1,329 @@
+/**
+ * 連投・荒らし対策のフィルター
+ * キューに積む前に、同じ・よく似たメッセージの連投と送信者ごとの連投を間引く
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+use super::item::{Source, SpeechRequest};
+
+/// 送信者ごとの連投を数える期間（ミリ秒）
+const THROTTLE_WINDOW_MS: u64 = 60_000;
+/// 重複判定のために覚えておくメッセージの数
+const HISTORY_LIMIT: usize = 256;
+/// よく似ているかを比べる長さ（文字数, 長文どうしの比較に時間を掛けないため）
+const COMPARE_CHARS: usize = 200;
+/// 同じ文字の繰り返しをこの回数に丸める（「wwwww」と「www」を同じとみなす）
+const MAX_REPEAT: usize = 3;
+/// 除外したメッセージを覚えておく数
+const RECENT_LIMIT: usize = 100;
+
+/// フィルターの設定（HTTPと棒読みちゃん互換サーバーから届いた読み上げにのみ適用する）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterConfig {
+    pub enabled: bool,
+    /// 同じ・よく似たメッセージを除外する期間（秒, 0で無効）
+    pub duplicate_window_secs: u64,
+    /// よく似ているとみなす類似度（0〜1, 1で完全一致のみ）
+    pub similarity: f32,
+    /// 送信者ごとの1分あたりの上限（0で無制限）
+    pub max_per_minute: u32,
+    /// 同じ送信者からこの間隔（ミリ秒）以内に続いたメッセージを1件にまとめる（0で無効）
+    pub merge_window_ms: u64,
+    /// まとめた読み上げの最大文字数
+    pub max_merged_chars: usize,
+}
+
+impl Default for FilterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            duplicate_window_secs: 30,
+            similarity: 0.9,
+            max_per_minute: 10,
+            merge_window_ms: 1500,
+            max_merged_chars: 300,
+        }
+    }
+}
+
+impl FilterConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(0.5..=1.0).contains(&self.similarity) {
+            return Err("類似度は0.5〜1の範囲で指定してください".to_string());
+        }
+        if self.duplicate_window_secs > 3600 {
+            return Err("重複を除外する期間は3600秒以下で指定してください".to_string());
+        }
+        if self.merge_window_ms > 10_000 {
+            return Err("まとめる間隔は10000ミリ秒以下で指定してください".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 除外・統合の理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterReason {
+    /// 同じ・よく似たメッセージが直前にあった
+    Duplicate,
+    /// 送信者の1分あたりの上限を超えた
+    Throttled,
+    /// 直前の読み上げにまとめた
+    Merged,
+}
+
+/// フィルターの判定
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Accept,
+    /// 指定した読み上げにまとめる（まだ待機中ならば）
+    MergeInto(String),
+    Reject(FilterReason),
+}
+
+/// 除外・統合したメッセージ
+#[derive(Debug, Clone, Serialize)]
+pub struct FilteredMessage {
+    pub reason: FilterReason,
+    pub text: String,
+    pub sender: Option<String>,
+    pub at: u64,
+}
+
+/// フィルターの集計
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FilterReport {
+    pub duplicates: u64,
+    pub throttled: u64,
+    pub merged: u64,
+    /// 直近に除外・統合したメッセージ（新しい順）
+    pub recent: Vec<FilteredMessage>,
+}
+
+/// 比較用に表記ゆれを均す（全角英数の半角化・小文字化・記号と空白の除去・繰り返しの丸め）
+pub fn normalize(text: &str) -> Vec<char> {
+    let mut normalized: Vec<char> = Vec::new();
+    let mut repeat = 0;
+    for c in text.chars() {
+        let c = match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        };
+        if !c.is_alphanumeric() {
+            continue;
+        }
+        for c in c.to_lowercase() {
+            if normalized.last() == Some(&c) {
+                repeat += 1;
+                if repeat >= MAX_REPEAT {
+                    continue;
+                }
+            } else {
+                repeat = 0;
+            }
+            normalized.push(c);
+        }
+    }
+    normalized.truncate(COMPARE_CHARS);
+    normalized
+}
+
+/// 類似度が threshold 以上になりうるか（編集距離は長さの差以上のため、類似度は短い方/長い方を超えない）
+fn may_be_similar(a: &[char], b: &[char], threshold: f32) -> bool {
+    let (shorter, longer) = (a.len().min(b.len()), a.len().max(b.len()));
+    longer == 0 || shorter as f32 / longer as f32 >= threshold
+}
+
+/// 編集距離にもとづく類似度（1で一致）
+pub fn similarity(a: &[char], b: &[char]) -> f32 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    1.0 - previous[b.len()] as f32 / longest as f32
+}
+
+#[derive(Default)]
+pub struct SpamFilter {
+    /// 最近受け付けたメッセージ（受け付けた時刻, 均した本文）
+    history: VecDeque<(u64, Vec<char>)>,
+    /// history にある本文ごとの件数（完全一致をすぐ見つけるため）
+    seen: HashMap<Vec<char>, usize>,
+    /// 送信者ごとの直近1分間の受付時刻
+    sent: HashMap<String, VecDeque<u64>>,
+    /// 送信者ごとの最後の読み上げ（読み上げID, 時刻）
+    last_item: HashMap<String, (String, u64)>,
+    report: FilterReport,
+}
+
+impl SpamFilter {
+    /// 読み上げ要求を受け付けるか判定し、受け付ける場合は記録する
+    pub fn check(&mut self, config: &FilterConfig, request: &SpeechRequest, now: u64) -> Verdict {
+        if !config.enabled || !matches!(request.source, Source::Http | Source::Bouyomi) {
+            return Verdict::Accept;
+        }
+
+        let normalized = normalize(&request.text);
+        if config.duplicate_window_secs > 0 && !normalized.is_empty() {
+            let window = config.duplicate_window_secs * 1000;
+            while self.history.front().is_some_and(|(at, _)| now.saturating_sub(*at) > window) {
+                self.forget_oldest();
+            }
+            let duplicate = self.seen.contains_key(&normalized)
+                || (config.similarity < 1.0
+                    && self.history.iter().any(|(_, text)| {
+                        may_be_similar(text, &normalized, config.similarity)
+                            && similarity(text, &normalized) >= config.similarity
+                    }));
+            if duplicate {
+                return self.reject(FilterReason::Duplicate, request, now);
+            }
+        }
+
+        if let Some(sender) = request.sender.as_deref() {
+            let sent = self.sent.entry(sender.to_string()).or_default();
+            while sent.front().is_some_and(|at| now.saturating_sub(*at) > THROTTLE_WINDOW_MS) {
+                sent.pop_front();
+            }
+            if config.max_per_minute > 0 && sent.len() >= config.max_per_minute as usize {
+                return self.reject(FilterReason::Throttled, request, now);
+            }
+            sent.push_back(now);
+        }
+        // 1分以上送ってこない送信者は忘れる
+        self.sent.retain(|_, sent| sent.back().is_some_and(|at| now.saturating_sub(*at) <= THROTTLE_WINDOW_MS));
+
+        if !normalized.is_empty() {
+            if self.history.len() == HISTORY_LIMIT {
+                self.forget_oldest();
+            }
+            *self.seen.entry(normalized.clone()).or_default() += 1;
+            self.history.push_back((now, normalized));
+        }
+
+        match (request.sender.as_deref(), config.merge_window_ms) {
+            (Some(sender), window) if window > 0 => match self.last_item.get(sender) {
+                Some((id, at)) if now.saturating_sub(*at) <= window => Verdict::MergeInto(id.clone()),
+                _ => Verdict::Accept,
+            },
+            _ => Verdict::Accept,
+        }
+    }
+
+    /// 最も古いメッセージを忘れる
+    fn forget_oldest(&mut self) {
+        let Some((_, text)) = self.history.pop_front() else {
+            return;
+        };
+        if let Some(count) = self.seen.get_mut(&text) {
+            *count -= 1;
+            if *count == 0 {
+                self.seen.remove(&text);
+            }
+        }
+    }
+
+    /// 送信者の最後の読み上げを記録する（まとめた場合は時刻を更新する）
+    pub fn note_item(&mut self, request: &SpeechRequest, id: &str, now: u64) {
+        if let Some(sender) = request.sender.as_deref() {
+            self.last_item.insert(sender.to_string(), (id.to_string(), now));
+        }
+        self.last_item.retain(|_, (_, at)| now.saturating_sub(*at) <= THROTTLE_WINDOW_MS);
+    }
+
+    /// 直前の読み上げにまとめたことを記録する
+    pub fn note_merged(&mut self, request: &SpeechRequest, id: &str, now: u64) {
+        self.note_item(request, id, now);
+        self.report.merged += 1;
+        self.remember(FilterReason::Merged, request, now);
+    }
+
+    fn reject(&mut self, reason: FilterReason, request: &SpeechRequest, now: u64) -> Verdict {
+        match reason {
+            FilterReason::Duplicate => self.report.duplicates += 1,
+            FilterReason::Throttled => self.report.throttled += 1,
+            FilterReason::Merged => self.report.merged += 1,
+        }
+        log::info!("[SpeechQueue] Filtered message from {:?}: {:?}", request.sender, reason);
+        self.remember(reason, request, now);
+        Verdict::Reject(reason)
+    }
+
+    fn remember(&mut self, reason: FilterReason, request: &SpeechRequest, now: u64) {
+        if self.report.recent.len() == RECENT_LIMIT {
+            self.report.recent.pop();
+        }
+        self.report.recent.insert(
+            0,
+            FilteredMessage {
+                reason,
+                text: request.text.clone(),
+                sender: request.sender.clone(),
+                at: now,
+            },
+        );
+    }
+
+    pub fn report(&self) -> FilterReport {
+        self.report.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chat(text: &str, sender: &str) -> SpeechRequest {
+        SpeechRequest {
+            source: Source::Http,
+            sender: Some(sender.to_string()),
+            ..SpeechRequest::new(text)
+        }
+    }
+
+    #[test]
+    fn test_near_duplicates_are_rejected() {
+        assert_eq!(normalize("ＡＢＣ ｗｗｗｗｗ！"), normalize("abc www"));
+
+        let config = FilterConfig { merge_window_ms: 0, ..FilterConfig::default() };
+        let mut filter = SpamFilter::default();
+        assert_eq!(filter.check(&config, &chat("こんにちは配信見てます", "a"), 0), Verdict::Accept);
+        assert_eq!(
+            filter.check(&config, &chat("こんにちは、配信見てます！！", "b"), 1_000),
+            Verdict::Reject(FilterReason::Duplicate)
+        );
+        // 期間を過ぎれば同じ内容でも読み上げる
+        assert_eq!(filter.check(&config, &chat("こんにちは配信見てます", "b"), 31_000), Verdict::Accept);
+        assert_eq!(filter.report().duplicates, 1);
+    }
+
+    #[test]
+    fn test_throttle_and_merge_per_sender() {
+        let config = FilterConfig { max_per_minute: 2, ..FilterConfig::default() };
+        let mut filter = SpamFilter::default();
+        let first = chat("一つ目", "a");
+        assert_eq!(filter.check(&config, &first, 0), Verdict::Accept);
+        filter.note_item(&first, "item-1", 0);
+        assert_eq!(filter.check(&config, &chat("二つ目", "a"), 1_000), Verdict::MergeInto("item-1".to_string()));
+        assert_eq!(
+            filter.check(&config, &chat("三つ目", "a"), 2_000),
+            Verdict::Reject(FilterReason::Throttled)
+        );
+        // ほかの送信者には影響しない
+        assert_eq!(filter.check(&config, &chat("四つ目", "b"), 2_000), Verdict::Accept);
+    }
+}
@@ -0,0 +1,152 @@
+/**
+ * 読み上げキューの要素
+ * 読み上げ要求の内容と優先度を表す
+ */
+
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::audio::effects::EffectConfig;
+use crate::tts::config::TTSConfig;
+
+/// 読み上げの優先度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl Priority {
+    /// 文字列から優先度を解釈する（未指定・不明な値は normal）
+    pub fn parse(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            Some("low") => Priority::Low,
+            Some("high") => Priority::High,
+            _ => Priority::Normal,
+        }
+    }
+}
+
+/// 読み上げ要求の送り元
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    /// アプリの画面から
+    #[default]
+    App,
+    /// HTTP API から
+    Http,
+    /// 予約読み上げから
+    Schedule,
+    /// 棒読みちゃん互換の通信から
+    Bouyomi,
+}
+
+/// 読み上げ要求（未指定の項目は読み上げ時点のTTS設定を使う）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpeechRequest {
+    pub text: String,
+    #[serde(default)]
+    pub source: Source,
+    /// 送信者（チャットのユーザー名など, 連投の判定に使う）
+    #[serde(default)]
+    pub sender: Option<String>,
+    #[serde(default)]
+    pub priority: Priority,
+    /// 読み上げるレーン（未指定なら既定のレーン, キューに積むときに決まる）
+    #[serde(default)]
+    pub lane: Option<String>,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub voice_id: Option<String>,
+    #[serde(default)]
+    pub voice_speed: Option<f32>,
+    #[serde(default)]
+    pub effects: Option<Vec<EffectConfig>>,
+    /// 音量（0〜1, 未指定なら1）
+    #[serde(default)]
+    pub volume: Option<f32>,
+}
+
+impl SpeechRequest {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    /// 要求で指定された項目をTTS設定に反映する
+    pub fn apply_to(&self, mut config: TTSConfig) -> TTSConfig {
+        if let Some(language) = self.language.as_deref() {
+            config = config.with_language(language);
+        }
+        if let Some(voice_id) = self.voice_id.clone() {
+            config = config.with_voice_id(voice_id);
+        }
+        if let Some(voice_speed) = self.voice_speed {
+            config = config.with_voice_speed(voice_speed);
+        }
+        config
+    }
+
+    /// 音声に掛ける倍率
+    pub fn gain(&self) -> f32 {
+        self.volume.map_or(1.0, |volume| volume.clamp(0.0, 1.0))
+    }
+
+    /// 使用するエフェクト（指定が無ければ声ごとの設定）
+    pub fn effects_for(&self, config: &TTSConfig) -> Vec<EffectConfig> {
+        self.effects.clone().unwrap_or_else(|| config.effects_for_voice())
+    }
+}
+
+/// キューに積まれた読み上げ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueItem {
+    /// 読み上げID（再生イベントの utterance_id と同じ）
+    pub id: String,
+    #[serde(flatten)]
+    pub request: SpeechRequest,
+    /// キューに積まれた時刻（UNIXエポックからのミリ秒）
+    pub enqueued_at: u64,
+    /// 同じ優先度の中での順番
+    pub sequence: u64,
+    /// 割り込みで中断され、続きからの再生を待っているか（再起動後は最初から読み上げる）
+    #[serde(skip)]
+    pub suspended: bool,
+    /// 溢れて捨てた読み上げの件数（「ほか N 件」の読み上げのみ）
+    #[serde(default)]
+    pub summarized: usize,
+}
+
+impl QueueItem {
+    /// 読み上げるレーン
+    pub fn lane(&self) -> &str {
+        self.request.lane.as_deref().unwrap_or_default()
+    }
+
+    /// テスト用の読み上げ（時刻・順番は0）
+    #[cfg(test)]
+    pub fn for_test(id: &str, request: SpeechRequest) -> Self {
+        Self {
+            id: id.to_string(),
+            request,
+            enqueued_at: 0,
+            sequence: 0,
+            suspended: false,
+            summarized: 0,
+        }
+    }
+}
+
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,20 @@
+/**
+ * 読み上げキューモジュール
+ * Tauriコマンド・HTTPサーバーから受け付けた読み上げを優先度順に合成・再生する
+ */
+
+pub mod backlog;
+pub mod config;
+pub mod filter;
+pub mod item;
+pub mod persist;
+pub mod prefetch;
+pub mod speech_queue;
+pub mod tracker;
+pub mod worker;
+
+pub use config::QueueConfig;
+pub use item::{Priority, Source, SpeechRequest};
+pub use persist::{QueueStore, RestoreReport};
+pub use filter::FilterReport;
+pub use speech_queue::{Admission, Interrupt, QueueSnapshot, Slot, SpeechQueue};
@@ -0,0 +1,229 @@
+/**
+ * 読み上げキューの保存と復元
+ * 待機中の読み上げをディスクへ書き出し、再起動後に読み込む
+ */
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::config::QueueConfig;
+use super::item::{now_millis, QueueItem};
+use super::speech_queue::SpeechQueue;
+use crate::tts::storage::app_data_dir;
+
+const QUEUE_FILE: &str = "speech_queue.json";
+const QUEUE_CONFIG_FILE: &str = "queue_config.json";
+/// 復元できなかった読み上げの記録（1行1件のJSON）
+const LOST_LOG_FILE: &str = "queue_lost.jsonl";
+/// 失われた読み上げの記録に残す件数
+const LOST_LOG_MAX_ENTRIES: usize = 1000;
+/// 記録がこの大きさを超えたら古いものを捨てる
+const LOST_LOG_COMPACT_BYTES: u64 = 1024 * 1024;
+/// 保存をまとめる間隔（変更が続いても、この間隔に1回だけ書き込む）
+const PERSIST_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// 一時ファイルに書いてから置き換え、書き込み途中で落ちても元のファイルを壊さない
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    {
+        let mut file = File::create(&tmp).with_context(|| format!("{:?} の作成に失敗しました", tmp))?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path).with_context(|| format!("{:?} の置き換えに失敗しました", path))?;
+    Ok(())
+}
+
+/// キューが変わったら少し待ってからまとめて保存する
+pub fn spawn_persister(queue: SpeechQueue) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            queue.persist_requested().await;
+            tokio::time::sleep(PERSIST_DEBOUNCE).await;
+            let queue = queue.clone();
+            if let Err(e) = tokio::task::spawn_blocking(move || queue.flush()).await {
+                log::error!("[SpeechQueue] Persist task failed: {}", e);
+            }
+        }
+    });
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedQueue {
+    saved_at: u64,
+    items: Vec<QueueItem>,
+}
+
+/// 読み上げが失われた理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LostReason {
+    /// 保持期間を過ぎていた
+    Expired,
+    /// キューの上限を超えて捨てられた
+    Overflow,
+}
+
+/// 失われた読み上げ
+#[derive(Debug, Clone, Serialize)]
+pub struct LostItem {
+    pub reason: LostReason,
+    pub lost_at: u64,
+    pub item: QueueItem,
+}
+
+/// 起動時の復元結果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RestoreReport {
+    /// 復元した読み上げの件数
+    pub restored: usize,
+    pub lost: Vec<LostItem>,
+    /// 保存ファイルを読めなかった場合のエラー（中身は失われる）
+    pub error: Option<String>,
+}
+
+/// 積まれてから `max_age_secs` 秒を過ぎた読み上げを取り分ける（0で無制限）
+pub fn split_expired(
+    items: Vec<QueueItem>,
+    now: u64,
+    max_age_secs: u64,
+) -> (Vec<QueueItem>, Vec<QueueItem>) {
+    if max_age_secs == 0 {
+        return (items, Vec::new());
+    }
+    items
+        .into_iter()
+        .partition(|item| now.saturating_sub(item.enqueued_at) <= max_age_secs * 1000)
+}
+
+/// キューと設定の保存先
+pub struct QueueStore {
+    dir: PathBuf,
+}
+
+impl QueueStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// アプリケーションのデータディレクトリに保存する
+    pub fn open_default() -> Result<Self> {
+        let dir = app_data_dir().map_err(|e| anyhow!("{}", e))?;
+        Ok(Self::new(dir))
+    }
+
+    fn queue_path(&self) -> PathBuf {
+        self.dir.join(QUEUE_FILE)
+    }
+
+    /// 読み上げ中・待機中の読み上げを保存する
+    pub fn save(&self, items: &[QueueItem]) -> Result<()> {
+        let persisted = PersistedQueue {
+            saved_at: now_millis(),
+            items: items.to_vec(),
+        };
+        let bytes = serde_json::to_vec(&persisted).context("キューのシリアライズに失敗しました")?;
+        write_atomic(&self.queue_path(), &bytes)
+    }
+
+    /// 保存された読み上げを読み込む（ファイルが無ければ空）
+    pub fn load(&self) -> Result<Vec<QueueItem>> {
+        let path = self.queue_path();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let bytes = fs::read(&path).with_context(|| format!("{:?} の読み込みに失敗しました", path))?;
+        let persisted: PersistedQueue =
+            serde_json::from_slice(&bytes).context("保存されたキューが壊れています")?;
+        Ok(persisted.items)
+    }
+
+    /// 保存されたキューを削除する
+    pub fn remove(&self) -> Result<()> {
+        let path = self.queue_path();
+        if path.exists() {
+            fs::remove_file(&path).with_context(|| format!("{:?} の削除に失敗しました", path))?;
+        }
+        Ok(())
+    }
+
+    /// 読めなかった保存ファイルを調査用に退避する
+    pub fn quarantine(&self) {
+        let path = self.queue_path();
+        let corrupt = path.with_extension(format!("corrupt-{}.json", now_millis()));
+        if let Err(e) = fs::rename(&path, &corrupt) {
+            log::error!("[SpeechQueue] Failed to move corrupt queue file: {}", e);
+        }
+    }
+
+    /// 失われた読み上げを記録に追記する
+    pub fn record_lost(&self, lost: &[LostItem]) -> Result<()> {
+        let path = self.dir.join(LOST_LOG_FILE);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("{:?} を開けませんでした", path))?;
+        for item in lost {
+            let line = serde_json::to_string(item)?;
+            writeln!(file, "{}", line)?;
+        }
+        if file.metadata()?.len() > LOST_LOG_COMPACT_BYTES {
+            compact_lost_log(&path, LOST_LOG_MAX_ENTRIES)?;
+        }
+        Ok(())
+    }
+
+    pub fn load_config(&self) -> QueueConfig {
+        QueueConfig::load(&self.dir.join(QUEUE_CONFIG_FILE))
+    }
+
+    pub fn save_config(&self, config: &QueueConfig) -> Result<()> {
+        config.save(&self.dir.join(QUEUE_CONFIG_FILE))
+    }
+}
+
+/// 失われた読み上げの記録を新しいものから指定件数だけ残す
+fn compact_lost_log(path: &Path, keep: usize) -> Result<()> {
+    let content = fs::read_to_string(path).with_context(|| format!("{:?} を読めませんでした", path))?;
+    let lines: Vec<&str> = content.lines().collect();
+    let kept = &lines[lines.len().saturating_sub(keep)..];
+    let mut bytes = kept.join("\n");
+    bytes.push('\n');
+    write_atomic(path, bytes.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::item::SpeechRequest;
+
+    fn item(id: &str, enqueued_at: u64) -> QueueItem {
+        QueueItem { enqueued_at, ..QueueItem::for_test(id, SpeechRequest::new(id)) }
+    }
+
+    #[test]
+    fn test_round_trip_and_expiry() {
+        let dir = std::env::temp_dir().join(format!("taneyomi-queue-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let store = QueueStore::new(dir.clone());
+        assert!(store.load().unwrap().is_empty());
+
+        let now = now_millis();
+        store.save(&[item("old", now - 120_000), item("new", now - 1_000)]).unwrap();
+        let items = store.load().unwrap();
+        assert_eq!(items.len(), 2);
+
+        let (kept, expired) = split_expired(items.clone(), now, 60);
+        assert_eq!(kept[0].id, "new");
+        assert_eq!(expired[0].id, "old");
+        // 0は無制限
+        assert_eq!(split_expired(items, now, 0).0.len(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
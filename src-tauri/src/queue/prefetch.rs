@@ -0,0 +1,258 @@
+/**
+ * 読み上げの先読み
+ * 読み上げ中に次の読み上げを合成してメモリに溜めておき、読み上げの間の無音を縮める
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc;
+
+use crate::commands::tts::{cancel_synthesis, resolve_api_key, TTSState};
+
+use super::config::LaneConfig;
+use super::item::QueueItem;
+use super::worker::start_synthesis;
+
+/// 先読みの設定
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrefetchConfig {
+    pub enabled: bool,
+    /// 先読みする件数（1〜2）
+    pub count: usize,
+    /// 先読みした音声を溜めておく上限（MB, 超えた読み上げは先読みをやめて順番が来てから合成する）
+    pub max_memory_mb: usize,
+}
+
+impl Default for PrefetchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            count: 2,
+            max_memory_mb: 32,
+        }
+    }
+}
+
+impl PrefetchConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=2).contains(&self.count) {
+            return Err("先読みする件数は1〜2で指定してください".to_string());
+        }
+        if !(1..=512).contains(&self.max_memory_mb) {
+            return Err("先読みに使うメモリは1〜512MBで指定してください".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 先読みした音声を溜められたか
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Buffered {
+    Ok,
+    /// 先読み中ではない（読み上げ中・先読みをやめた）
+    NotPrefetching,
+    /// 上限を超えたため先読みをやめた
+    OverLimit,
+}
+
+struct Prefetched {
+    /// 先読みしたときの本文（まとめられて変わったら合成し直す）
+    text: String,
+    audio: mpsc::UnboundedReceiver<Vec<f32>>,
+    bytes: usize,
+}
+
+#[derive(Default)]
+struct PrefetchState {
+    items: HashMap<String, Prefetched>,
+    bytes: usize,
+    max_bytes: usize,
+    /// 上限超過・失敗で先読みをやめた読み上げ（順番が来てから改めて合成する）
+    abandoned: HashSet<String>,
+}
+
+/// 先読みした音声の置き場（読み上げワーカーが読み上げるときに受け取る）
+#[derive(Clone, Default)]
+pub struct Prefetcher {
+    state: Arc<Mutex<PrefetchState>>,
+}
+
+impl Prefetcher {
+    fn lock(&self) -> std::sync::MutexGuard<'_, PrefetchState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn insert(&self, id: &str, text: &str, audio: mpsc::UnboundedReceiver<Vec<f32>>, max_bytes: usize) {
+        let mut state = self.lock();
+        state.max_bytes = max_bytes;
+        state.items.insert(id.to_string(), Prefetched { text: text.to_string(), audio, bytes: 0 });
+    }
+
+    /// 先読みした音声を受け取る（以後は読み上げ中として扱い、溜めた量に数えない）
+    pub fn claim(&self, id: &str) -> Option<mpsc::UnboundedReceiver<Vec<f32>>> {
+        let mut state = self.lock();
+        let prefetched = state.items.remove(id)?;
+        state.bytes -= prefetched.bytes;
+        Some(prefetched.audio)
+    }
+
+    /// 先読み中の読み上げが受信した音声の量を数える
+    pub fn account(&self, id: &str, bytes: usize) -> Buffered {
+        let mut state = self.lock();
+        if !state.items.contains_key(id) {
+            return Buffered::NotPrefetching;
+        }
+        if state.bytes + bytes > state.max_bytes {
+            state.remove(id);
+            state.abandoned.insert(id.to_string());
+            return Buffered::OverLimit;
+        }
+        state.bytes += bytes;
+        if let Some(prefetched) = state.items.get_mut(id) {
+            prefetched.bytes += bytes;
+        }
+        Buffered::Ok
+    }
+
+    /// 先読みをやめる（先読み中だったか）
+    pub fn abandon(&self, id: &str) -> bool {
+        let mut state = self.lock();
+        let prefetching = state.remove(id);
+        if prefetching {
+            state.abandoned.insert(id.to_string());
+        }
+        prefetching
+    }
+
+    /// 待機中の読み上げからレーンごとに先読みする読み上げを決め、不要になった先読みを捨てる
+    /// 先読みを始める読み上げと、捨てた読み上げのIDを返す
+    fn plan(&self, current: &[&str], pending: &[QueueItem], count: usize) -> (Vec<QueueItem>, Vec<String>) {
+        let mut state = self.lock();
+        state.abandoned.retain(|id| pending.iter().any(|item| &item.id == id));
+
+        // 中断中の読み上げは音声を持っており、「ほか N 件」は件数が変わるため先読みしない
+        let mut per_lane: HashMap<&str, usize> = HashMap::new();
+        let wanted: Vec<&QueueItem> = pending
+            .iter()
+            .filter(|item| !item.suspended && item.summarized == 0 && !state.abandoned.contains(&item.id))
+            .filter(|item| {
+                let taken = per_lane.entry(item.lane()).or_default();
+                *taken += 1;
+                *taken <= count
+            })
+            .collect();
+
+        let discarded: Vec<String> = state
+            .items
+            .iter()
+            .filter(|(id, prefetched)| {
+                // 取り出された直後でまだ受け取っていない読み上げは捨てない
+                !current.contains(&id.as_str())
+                    && !wanted.iter().any(|item| &item.id == *id && item.request.text == prefetched.text)
+            })
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in &discarded {
+            state.remove(id);
+        }
+
+        let started = wanted
+            .into_iter()
+            .filter(|item| !state.items.contains_key(&item.id))
+            .cloned()
+            .collect();
+        (started, discarded)
+    }
+}
+
+impl PrefetchState {
+    fn remove(&mut self, id: &str) -> bool {
+        match self.items.remove(id) {
+            Some(prefetched) => {
+                self.bytes -= prefetched.bytes;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// キューが変わるたびに先読みを見直す（アプリ起動時に1度だけ呼ぶ）
+pub fn spawn_prefetcher(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let queue = app.state::<TTSState>().queue.clone();
+        loop {
+            queue.changed().await;
+            let state = app.state::<TTSState>();
+            prefetch(&app, state.inner()).await;
+        }
+    });
+}
+
+async fn prefetch(app: &AppHandle, state: &TTSState) {
+    let config = state.queue.config().prefetch;
+    let count = if config.enabled { config.count } else { 0 };
+    let snapshot = state.queue.snapshot();
+    let current: Vec<&str> = snapshot.current.iter().map(|item| item.id.as_str()).collect();
+    let (started, discarded) = state.prefetcher.plan(&current, &snapshot.pending, count);
+
+    for id in discarded {
+        log::info!("[SpeechQueue] Discarding prefetched {}", id);
+        cancel_synthesis(state, &id).await;
+    }
+    if started.is_empty() {
+        return;
+    }
+    let Ok(api_key) = resolve_api_key(state).await else {
+        return;
+    };
+
+    let max_bytes = config.max_memory_mb * 1024 * 1024;
+    for item in started {
+        log::info!("[SpeechQueue] Prefetching {}", item.id);
+        let catch_up = state.queue.catch_up(item.lane());
+        let lane = state.queue.lane_config(item.lane()).unwrap_or_else(|| LaneConfig::new(item.lane()));
+        let config = item.request.apply_to(lane.apply_to(state.config.lock().await.clone()));
+        let (audio_tx, audio_rx) = mpsc::unbounded_channel();
+        state.prefetcher.insert(&item.id, &item.request.text, audio_rx, max_bytes);
+        let text = catch_up.truncate(&item.request.text);
+        start_synthesis(app, state, &item, text, config, api_key.clone(), audio_tx).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::item::SpeechRequest;
+
+    fn item(id: &str) -> QueueItem {
+        QueueItem::for_test(id, SpeechRequest::new(id))
+    }
+
+    #[test]
+    fn test_plan_and_memory_limit() {
+        let prefetcher = Prefetcher::default();
+        let pending = vec![item("a"), item("b"), item("c")];
+        let (started, discarded) = prefetcher.plan(&[], &pending, 2);
+        assert_eq!(started.iter().map(|i| i.id.as_str()).collect::<Vec<_>>(), ["a", "b"]);
+        assert!(discarded.is_empty());
+        for item in &started {
+            prefetcher.insert(&item.id, &item.request.text, mpsc::unbounded_channel().1, 100);
+        }
+
+        // 上限を超えた読み上げは先読みをやめ、次の読み上げを先読みする
+        assert_eq!(prefetcher.account("a", 60), Buffered::Ok);
+        assert_eq!(prefetcher.account("b", 60), Buffered::OverLimit);
+        assert_eq!(prefetcher.account("b", 10), Buffered::NotPrefetching);
+        let (started, _) = prefetcher.plan(&[], &pending, 2);
+        assert_eq!(started[0].id, "c");
+
+        // キューから消えた読み上げの先読みは捨てる
+        let (_, discarded) = prefetcher.plan(&[], &pending[2..], 2);
+        assert_eq!(discarded, ["a"]);
+        assert!(prefetcher.claim("a").is_none());
+    }
+}
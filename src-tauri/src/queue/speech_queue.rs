@@ -0,0 +1,847 @@
+/**
+ * 優先度付き読み上げキュー
+ * レーンごとに、優先度の高い順、同じ優先度では積まれた順に読み上げを取り出す
+ */
+
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+use super::backlog::{overflow_victim, summary_text, CatchUp, OverflowPolicy};
+use super::config::{LaneConfig, PreemptPolicy, QueueConfig};
+use super::filter::{FilterReason, FilterReport, SpamFilter, Verdict};
+use super::item::{now_millis, QueueItem, SpeechRequest};
+use super::persist::{split_expired, LostItem, LostReason, QueueStore, RestoreReport};
+use super::tracker::{SpeechState, Tracker};
+
+/// 読み上げを取り出す枠
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Slot {
+    /// レーンごとの通常の読み上げ（1件ずつ順番に）
+    Main(String),
+    /// 読み上げ中の読み上げに重ねる割り込み（全レーン共通）
+    Overlay,
+}
+
+/// 読み上げ中の要素への割り込み（後ろほど強く、重なった場合は強いほうを採る）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Interrupt {
+    /// 一時退避し、割り込んだ読み上げの後に続きから読み上げる
+    Suspend,
+    /// フェードアウトして破棄する
+    Drop,
+    /// 停止・スキップされた（音声は呼び出し側で停止済み）
+    Cancel,
+}
+
+/// 読み上げ中の要素に割り込みを伝える
+#[derive(Default)]
+pub struct ItemControl {
+    interrupt: Mutex<Option<Interrupt>>,
+    notify: Notify,
+}
+
+impl ItemControl {
+    pub fn signal(&self, interrupt: Interrupt) {
+        let mut current = self.interrupt.lock().unwrap_or_else(|p| p.into_inner());
+        *current = (*current).max(Some(interrupt));
+        drop(current);
+        self.notify.notify_one();
+    }
+
+    /// 割り込まれるまで待つ
+    pub async fn interrupted(&self) -> Interrupt {
+        loop {
+            let notified = self.notify.notified();
+            if let Some(interrupt) = self.interrupt.lock().unwrap_or_else(|p| p.into_inner()).take() {
+                return interrupt;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// キューの状態（フロントエンド・HTTP向け）
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueSnapshot {
+    /// 一時停止中か
+    pub paused: bool,
+    /// レーンごとの読み上げ中の要素（レーンの設定順）
+    pub current: Vec<QueueItem>,
+    /// 全レーンの待機中の要素（読み上げる順）
+    pub pending: Vec<QueueItem>,
+    /// 重ねて読み上げ中・待機中の割り込み（読み上げる順）
+    pub overlay: Vec<QueueItem>,
+}
+
+struct Active {
+    item: QueueItem,
+    control: Arc<ItemControl>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    /// 全レーンの待機中の要素
+    items: Vec<QueueItem>,
+    next_sequence: u64,
+    /// レーンごとの読み上げ中の要素
+    current: HashMap<String, Active>,
+    /// 重ねて読み上げるのを待っている割り込み
+    overlays: Vec<QueueItem>,
+    overlay_current: Option<Active>,
+    config: QueueConfig,
+    restore_report: Option<RestoreReport>,
+    /// ワーカーが動いているレーン
+    workers: HashSet<String>,
+    /// 一時停止中は次の読み上げを取り出さない
+    paused: bool,
+}
+
+impl QueueState {
+    fn pending(&mut self, slot: &Slot) -> &mut Vec<QueueItem> {
+        match slot {
+            Slot::Main(_) => &mut self.items,
+            Slot::Overlay => &mut self.overlays,
+        }
+    }
+
+    fn active(&self, slot: &Slot) -> Option<&Active> {
+        match slot {
+            Slot::Main(lane) => self.current.get(lane),
+            Slot::Overlay => self.overlay_current.as_ref(),
+        }
+    }
+
+    fn set_active(&mut self, slot: &Slot, active: Option<Active>) {
+        match (slot, active) {
+            (Slot::Main(lane), Some(active)) => {
+                self.current.insert(lane.clone(), active);
+            }
+            (Slot::Main(lane), None) => {
+                self.current.remove(lane);
+            }
+            (Slot::Overlay, active) => self.overlay_current = active,
+        }
+    }
+
+    /// 要素を読み上げるレーン（設定から消えたレーンは既定のレーン）
+    fn lane_of<'a>(&'a self, item: &'a QueueItem) -> &'a str {
+        self.config
+            .resolve_lane(Some(item.lane()))
+            .map_or("", |lane| lane.name.as_str())
+    }
+
+    /// レーンの待機中の要素の件数
+    fn count_in(&self, lane: &str) -> usize {
+        self.items.iter().filter(|item| self.lane_of(item) == lane).count()
+    }
+}
+
+/// 読み上げ要求の受付結果
+#[derive(Debug, Clone)]
+pub enum Admission {
+    /// キューに積んだ
+    Queued(QueueItem),
+    /// 同じ送信者の待機中の読み上げにまとめた
+    Merged(QueueItem),
+    /// 連投・荒らし対策で除外した
+    Filtered(FilterReason),
+    /// キューの上限を超えたため積まなかった
+    Overflowed,
+}
+
+/// まとめた読み上げの区切り（文末の句読点が無ければ補う）
+fn join_text(text: &mut String, addition: &str) {
+    if !text.ends_with(['。', '！', '？', '!', '?', '.', '、']) {
+        text.push('。');
+    }
+    text.push_str(addition.trim());
+}
+
+/// 次に読み上げる要素の位置（優先度の高い順、同じ優先度では積まれた順）
+fn next_turn<'a>(items: impl Iterator<Item = (usize, &'a QueueItem)>) -> Option<usize> {
+    items
+        .max_by(|(_, a), (_, b)| {
+            a.request
+                .priority
+                .cmp(&b.request.priority)
+                .then(b.sequence.cmp(&a.sequence))
+        })
+        .map(|(index, _)| index)
+}
+
+/// 読み上げる順に並べる
+fn sort_by_turn(items: &mut [QueueItem]) {
+    items.sort_by(|a, b| {
+        b.request
+            .priority
+            .cmp(&a.request.priority)
+            .then(a.sequence.cmp(&b.sequence))
+    });
+}
+
+/// Tauriコマンド・HTTPサーバー・読み上げワーカーで共有するキュー
+#[derive(Clone, Default)]
+pub struct SpeechQueue {
+    state: Arc<Mutex<QueueState>>,
+    /// 連投・荒らし対策（キューより先にロックし、判定中はキューをロックしない）
+    filter: Arc<Mutex<SpamFilter>>,
+    /// 通常の読み上げが積まれたことを全レーンのワーカーに伝える
+    notify: Arc<Notify>,
+    overlay_notify: Arc<Notify>,
+    /// レーンの設定が変わったことをワーカーの管理タスクに伝える
+    lanes_changed: Arc<Notify>,
+    /// キューが変わったことを先読みに伝える
+    changed: Arc<Notify>,
+    /// 保存先（未設定ならディスクに保存しない）
+    store: Arc<Mutex<Option<QueueStore>>>,
+    /// 前回の保存からキューが変わったか
+    dirty: Arc<AtomicBool>,
+    /// 保存を頼まれたことを保存タスクに伝える
+    persist_requested: Arc<Notify>,
+    /// 読み上げごとの進み具合
+    tracker: Tracker,
+}
+
+impl SpeechQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        // ロック中にパニックしても中身は整合しているため、そのまま使い続ける
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_filter(&self) -> std::sync::MutexGuard<'_, SpamFilter> {
+        self.filter.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn notify(&self, slot: &Slot) -> &Notify {
+        match slot {
+            Slot::Main(_) => &self.notify,
+            Slot::Overlay => &self.overlay_notify,
+        }
+    }
+
+    /// 待っているワーカーを起こす（通常の読み上げはレーンごとのワーカー全てが確かめる）
+    fn wake(&self, slot: &Slot) {
+        match slot {
+            Slot::Main(_) => self.notify.notify_waiters(),
+            Slot::Overlay => self.overlay_notify.notify_one(),
+        }
+    }
+
+    /// 連投・荒らし対策を通してから読み上げを積む
+    /// 設定に無いレーンを指定した読み上げは既定のレーンに積む
+    pub fn enqueue(&self, mut request: SpeechRequest) -> Admission {
+        let now = now_millis();
+        let (lane, config) = {
+            let state = self.lock();
+            let lane = state.config.resolve_lane(request.lane.as_deref()).map(|lane| lane.name.clone());
+            (lane, state.config.filter.clone())
+        };
+        if request.lane.is_some() && request.lane != lane {
+            log::warn!("[SpeechQueue] Unknown lane {:?}, using {:?}", request.lane, lane);
+        }
+        request.lane = lane;
+        // 判定は重いことがあるため、キューをロックせずに行う
+        let mut filter = self.lock_filter();
+        let verdict = filter.check(&config, &request, now);
+        if let Verdict::Reject(reason) = verdict {
+            return Admission::Filtered(reason);
+        }
+        let mut state = self.lock();
+        if let Verdict::MergeInto(id) = verdict {
+            // まだ読み上げていなければ、同じ送信者の直前の読み上げにまとめる
+            let merged = state.items.iter_mut().find(|item| {
+                item.id == id
+                    && !item.suspended
+                    && item.request.lane == request.lane
+                    && item.request.priority == request.priority
+                    && item.request.volume == request.volume
+                    && item.request.text.chars().count() + request.text.chars().count() <= config.max_merged_chars
+            });
+            if let Some(item) = merged {
+                join_text(&mut item.request.text, &request.text);
+                let item = item.clone();
+                self.tracker.track(&item);
+                filter.note_merged(&request, &item.id, now);
+                drop(state);
+                drop(filter);
+                log::info!("[SpeechQueue] Merged message into {}", item.id);
+                self.persist();
+                return Admission::Merged(item);
+            }
+        }
+        let item = QueueItem {
+            id: uuid::Uuid::new_v4().to_string(),
+            request,
+            enqueued_at: now,
+            sequence: state.next_sequence,
+            suspended: false,
+            summarized: 0,
+        };
+        state.next_sequence += 1;
+
+        let dropped = self.make_room(&mut state, &item);
+        let admission = match dropped {
+            Some(ref dropped) if dropped.id == item.id => Admission::Overflowed,
+            _ => {
+                self.tracker.track(&item);
+                self.push(&mut state, item.clone());
+                filter.note_item(&item.request, &item.id, now);
+                Admission::Queued(item)
+            }
+        };
+        drop(state);
+        drop(filter);
+
+        if let Some(dropped) = dropped {
+            self.record_overflow(dropped, now);
+        }
+        self.persist();
+        admission
+    }
+
+    /// レーンの待機中の読み上げが上限に達していれば、設定に従って1件捨てて空きを作る
+    /// 積もうとしている読み上げを捨てることもある（捨てた読み上げを返す）
+    fn make_room(&self, state: &mut QueueState, item: &QueueItem) -> Option<QueueItem> {
+        let lane = item.lane().to_string();
+        let backlog = &state.config.backlog;
+        let in_lane: Vec<&QueueItem> = state.items.iter().filter(|item| state.lane_of(item) == lane).collect();
+        let counted = in_lane.iter().filter(|item| item.summarized == 0).count();
+        if backlog.max_length == 0 || counted < backlog.max_length {
+            return None;
+        }
+        let policy = backlog.overflow;
+        let victim = overflow_victim(in_lane.into_iter().chain(Some(item)), policy)?.id.clone();
+        if victim == item.id {
+            return Some(item.clone());
+        }
+        let index = state.items.iter().position(|item| item.id == victim)?;
+        let dropped = state.items.remove(index);
+
+        if policy == OverflowPolicy::Summarize {
+            let summary = state
+                .items
+                .iter()
+                .position(|item| item.summarized > 0 && state.lane_of(item) == lane);
+            match summary.map(|index| &mut state.items[index]) {
+                Some(summary) => {
+                    summary.summarized += 1;
+                    summary.request.text = summary_text(summary.summarized);
+                }
+                None => {
+                    // 捨てた読み上げの順番で「ほか N 件」と読み上げる
+                    let summary = QueueItem {
+                        id: uuid::Uuid::new_v4().to_string(),
+                        request: SpeechRequest {
+                            priority: dropped.request.priority,
+                            lane: Some(lane),
+                            ..SpeechRequest::new(summary_text(1))
+                        },
+                        enqueued_at: dropped.enqueued_at,
+                        sequence: dropped.sequence,
+                        suspended: false,
+                        summarized: 1,
+                    };
+                    self.tracker.track(&summary);
+                    state.items.push(summary);
+                }
+            }
+        }
+        Some(dropped)
+    }
+
+    /// 上限を超えて捨てた読み上げを記録する
+    fn record_overflow(&self, item: QueueItem, now: u64) {
+        log::warn!("[SpeechQueue] Queue is full, dropped {}", item.id);
+        self.tracker.fail(&item.id, SpeechState::Cancelled, "キューがいっぱいのため破棄しました");
+        let store = self.store.lock().unwrap_or_else(|p| p.into_inner());
+        if let Some(store) = store.as_ref() {
+            let lost = LostItem { reason: LostReason::Overflow, lost_at: now, item };
+            if let Err(e) = store.record_lost(&[lost]) {
+                log::error!("[SpeechQueue] Failed to record lost items: {:#}", e);
+            }
+        }
+    }
+
+    /// 読み上げを積む
+    /// 同じレーンで読み上げ中の読み上げより優先度が高ければ、設定に従って割り込む
+    fn push(&self, state: &mut QueueState, item: QueueItem) {
+        let policy = state.config.preemption.policy_for(item.request.priority);
+        let lane = Slot::Main(item.lane().to_string());
+        let preempted = state
+            .active(&lane)
+            .filter(|active| item.request.priority > active.item.request.priority);
+        let slot = match (policy, preempted) {
+            (PreemptPolicy::InterruptResume, Some(active)) => {
+                active.control.signal(Interrupt::Suspend);
+                lane
+            }
+            (PreemptPolicy::InterruptDrop, Some(active)) => {
+                active.control.signal(Interrupt::Drop);
+                lane
+            }
+            (PreemptPolicy::Overlay, Some(_)) => Slot::Overlay,
+            _ => lane,
+        };
+        log::info!("[SpeechQueue] Enqueued {} ({:?}, {:?}, {:?})", item.id, item.request.priority, policy, slot);
+        state.pending(&slot).push(item);
+        self.wake(&slot);
+    }
+
+    /// 次に読み上げる要素を取り出し、読み上げ中として記録する
+    pub fn pop_next(&self, slot: &Slot) -> Option<(QueueItem, Arc<ItemControl>)> {
+        let mut state = self.lock();
+        if state.paused {
+            return None;
+        }
+        let index = match slot {
+            Slot::Main(lane) => next_turn(
+                state.items.iter().enumerate().filter(|(_, item)| state.lane_of(item) == lane),
+            ),
+            Slot::Overlay => next_turn(state.overlays.iter().enumerate()),
+        }?;
+        let item = state.pending(slot).remove(index);
+        let control = Arc::new(ItemControl::default());
+        state.set_active(slot, Some(Active { item: item.clone(), control: control.clone() }));
+        // 読み上げ中の要素も保存するため保存はしないが、次の読み上げの先読みは始める
+        self.changed.notify_one();
+        Some((item, control))
+    }
+
+    /// 次の要素が積まれるまで待って取り出す
+    /// レーンが設定から消えたらNoneを返す（待機中の要素は既定のレーンで読み上げる）
+    pub async fn next(&self, slot: &Slot) -> Option<(QueueItem, Arc<ItemControl>)> {
+        loop {
+            let notified = self.notify(slot).notified();
+            if let Slot::Main(lane) = slot {
+                let mut state = self.lock();
+                if state.config.lane(lane).is_none() {
+                    state.workers.remove(lane);
+                    return None;
+                }
+            }
+            if let Some(next) = self.pop_next(slot) {
+                return Some(next);
+            }
+            notified.await;
+        }
+    }
+
+    /// ワーカーが動いていないレーンを、動いているものとして記録して返す
+    pub fn start_lanes(&self) -> Vec<String> {
+        let mut state = self.lock();
+        let started: Vec<String> = state
+            .config
+            .lanes
+            .iter()
+            .filter(|lane| !state.workers.contains(&lane.name))
+            .map(|lane| lane.name.clone())
+            .collect();
+        state.workers.extend(started.iter().cloned());
+        started
+    }
+
+    /// レーンの設定が変わるまで待つ
+    pub async fn lanes_changed(&self) {
+        self.lanes_changed.notified().await;
+    }
+
+    /// 読み上げ中の要素の処理が終わった
+    /// 読み上げ中・待機中の読み上げが無くなったかを返す
+    pub fn finish(&self, slot: &Slot) -> bool {
+        let idle = {
+            let mut state = self.lock();
+            state.set_active(slot, None);
+            state.current.is_empty()
+                && state.overlay_current.is_none()
+                && state.items.is_empty()
+                && state.overlays.is_empty()
+        };
+        self.persist();
+        idle
+    }
+
+    /// 中断した要素を、続きから読み上げるために積み直す（順番は元のまま）
+    pub fn requeue_suspended(&self, mut item: QueueItem) {
+        item.suspended = true;
+        log::info!("[SpeechQueue] Suspended {}", item.id);
+        self.lock().items.push(item);
+        self.persist();
+        self.notify.notify_waiters();
+    }
+
+    /// 読み上げ中の要素に割り込む
+    pub fn interrupt(&self, slot: &Slot, interrupt: Interrupt) -> Option<QueueItem> {
+        let state = self.lock();
+        let active = state.active(slot)?;
+        active.control.signal(interrupt);
+        Some(active.item.clone())
+    }
+
+    /// 読み上げ中の要素のうち、指定した読み上げに割り込む
+    pub fn interrupt_item(&self, id: &str, interrupt: Interrupt) -> Option<QueueItem> {
+        let state = self.lock();
+        let active = state
+            .current
+            .values()
+            .chain(state.overlay_current.iter())
+            .find(|active| active.item.id == id)?;
+        active.control.signal(interrupt);
+        Some(active.item.clone())
+    }
+
+    /// 全レーンの読み上げ中の要素と割り込みに割り込む
+    pub fn interrupt_all(&self, interrupt: Interrupt) -> Vec<QueueItem> {
+        let state = self.lock();
+        state
+            .current
+            .values()
+            .chain(state.overlay_current.iter())
+            .map(|active| {
+                active.control.signal(interrupt);
+                active.item.clone()
+            })
+            .collect()
+    }
+
+    /// いずれかのレーンで読み上げ中か
+    pub fn is_speaking(&self) -> bool {
+        !self.lock().current.is_empty()
+    }
+
+    /// 待機中の要素（重ねて読み上げる割り込みを含む）を1件取り除く
+    pub fn remove(&self, id: &str) -> Option<QueueItem> {
+        let removed = {
+            let mut state = self.lock();
+            let state = &mut *state;
+            let removed = [&mut state.items, &mut state.overlays].into_iter().find_map(|pending| {
+                let index = pending.iter().position(|item| item.id == id)?;
+                Some(pending.remove(index))
+            });
+            removed
+        };
+        if let Some(item) = removed.as_ref() {
+            self.tracker.advance(&item.id, SpeechState::Cancelled);
+            self.persist();
+        }
+        removed
+    }
+
+    /// 一時停止する・再開する（読み上げ中の音声の一時停止は呼び出し側で行う）
+    pub fn set_paused(&self, paused: bool) {
+        self.lock().paused = paused;
+        if !paused {
+            self.notify.notify_waiters();
+            self.overlay_notify.notify_one();
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.lock().paused
+    }
+
+    /// 待機中の要素（重ねて読み上げる割り込みを含む）を全て取り除く（読み上げ中の要素はそのまま）
+    pub fn clear(&self) -> Vec<QueueItem> {
+        let mut cleared = {
+            let mut state = self.lock();
+            let mut cleared = std::mem::take(&mut state.items);
+            cleared.append(&mut state.overlays);
+            cleared
+        };
+        sort_by_turn(&mut cleared);
+        for item in &cleared {
+            self.tracker.advance(&item.id, SpeechState::Cancelled);
+        }
+        self.persist();
+        cleared
+    }
+
+    /// 読み上げ中の要素と待機中の要素（レーンは読み上げるレーンに置き換える）
+    pub fn snapshot(&self) -> QueueSnapshot {
+        let state = self.lock();
+        let resolve = |item: &QueueItem| {
+            let lane = state.lane_of(item).to_string();
+            let mut item = item.clone();
+            item.request.lane = Some(lane);
+            item
+        };
+        let mut current: Vec<QueueItem> = state.current.values().map(|active| resolve(&active.item)).collect();
+        current.sort_by_key(|item| {
+            state.config.lanes.iter().position(|lane| lane.name == item.lane()).unwrap_or(usize::MAX)
+        });
+        let mut pending: Vec<QueueItem> = state.items.iter().map(resolve).collect();
+        sort_by_turn(&mut pending);
+        let mut overlays: Vec<QueueItem> = state.overlays.iter().map(resolve).collect();
+        sort_by_turn(&mut overlays);
+        let overlay = state
+            .overlay_current
+            .iter()
+            .map(|active| resolve(&active.item))
+            .chain(overlays)
+            .collect();
+        QueueSnapshot { paused: state.paused, current, pending, overlay }
+    }
+
+    /// レーンの待機中の読み上げの件数に応じた追いつき方
+    pub fn catch_up(&self, lane: &str) -> CatchUp {
+        let state = self.lock();
+        CatchUp::for_backlog(&state.config.backlog.catch_up, state.count_in(lane))
+    }
+
+    pub fn config(&self) -> QueueConfig {
+        self.lock().config.clone()
+    }
+
+    /// 読み上げるレーンの設定（設定から消えたレーンは既定のレーン）
+    pub fn lane_config(&self, lane: &str) -> Option<LaneConfig> {
+        self.lock().config.resolve_lane(Some(lane)).cloned()
+    }
+
+    /// 読み上げ要求で指定されたレーンが設定にあるか確かめる
+    pub fn check_lane(&self, lane: Option<&str>) -> Result<(), String> {
+        match lane {
+            Some(name) if self.lock().config.lane(name).is_none() => Err(format!("不明なレーンです: {}", name)),
+            _ => Ok(()),
+        }
+    }
+
+    /// 設定を更新し、保存先があれば設定も保存する
+    pub fn set_config(&self, config: QueueConfig) -> Result<(), String> {
+        if let Some(store) = self.store.lock().unwrap_or_else(|p| p.into_inner()).as_ref() {
+            store
+                .save_config(&config)
+                .map_err(|e| format!("キュー設定の保存に失敗しました: {}", e))?;
+        }
+        self.lock().config = config;
+        self.persist();
+        // 消えたレーンのワーカーを止め、追加されたレーンのワーカーを起動させる
+        self.notify.notify_waiters();
+        self.lanes_changed.notify_one();
+        Ok(())
+    }
+
+    /// 読み上げごとの進み具合
+    pub fn tracker(&self) -> &Tracker {
+        &self.tracker
+    }
+
+    /// 連投・荒らし対策で除外・統合したメッセージの集計
+    pub fn filter_report(&self) -> FilterReport {
+        self.lock_filter().report()
+    }
+
+    /// 起動時の復元結果
+    pub fn restore_report(&self) -> Option<RestoreReport> {
+        self.lock().restore_report.clone()
+    }
+
+    /// 保存先を設定し、前回保存された読み上げを復元する（起動時に1度だけ呼ぶ）
+    pub fn restore(&self, store: QueueStore) -> RestoreReport {
+        let config = store.load_config();
+        let mut report = RestoreReport::default();
+        let mut restored = Vec::new();
+
+        if config.persistence.enabled {
+            match store.load() {
+                Ok(items) => {
+                    let now = now_millis();
+                    let (kept, expired) = split_expired(items, now, config.persistence.max_age_secs);
+                    restored = kept;
+                    report.lost = expired
+                        .into_iter()
+                        .map(|item| LostItem { reason: LostReason::Expired, lost_at: now, item })
+                        .collect();
+                }
+                Err(e) => {
+                    log::error!("[SpeechQueue] Failed to load saved queue: {:#}", e);
+                    report.error = Some(format!("{:#}", e));
+                    store.quarantine();
+                }
+            }
+        }
+        report.restored = restored.len();
+
+        if !report.lost.is_empty() {
+            log::warn!("[SpeechQueue] Dropped {} expired items on restore", report.lost.len());
+            if let Err(e) = store.record_lost(&report.lost) {
+                log::error!("[SpeechQueue] Failed to record lost items: {:#}", e);
+            }
+        }
+
+        {
+            let mut state = self.lock();
+            let next_sequence = restored.iter().map(|item| item.sequence + 1).max().unwrap_or(0);
+            state.next_sequence = state.next_sequence.max(next_sequence);
+            for item in &restored {
+                self.tracker.track(item);
+            }
+            // 復元した読み上げを起動後に積まれたものより先に並べる
+            state.items.splice(0..0, restored);
+            state.config = config;
+            state.restore_report = Some(report.clone());
+        }
+        *self.store.lock().unwrap_or_else(|p| p.into_inner()) = Some(store);
+        self.persist();
+
+        log::info!("[SpeechQueue] Restored {} items", report.restored);
+        self.notify.notify_waiters();
+        self.lanes_changed.notify_one();
+        report
+    }
+
+    /// キューが変わるまで待つ
+    pub async fn changed(&self) {
+        self.changed.notified().await;
+    }
+
+    /// 保存を頼む（実際の書き込みは保存タスクがまとめて行う）
+    /// キューを変えるたびに呼ばれるため、変わったことの通知も兼ねる
+    fn persist(&self) {
+        self.changed.notify_one();
+        self.dirty.store(true, Ordering::Release);
+        self.persist_requested.notify_one();
+    }
+
+    /// 保存を頼まれるまで待つ
+    pub async fn persist_requested(&self) {
+        self.persist_requested.notified().await;
+    }
+
+    /// 前回の保存から変わっていれば、読み上げ中・待機中の要素を保存する（保存が無効なら保存ファイルを消す）
+    /// ファイルへ書き込むため、非同期タスクからは spawn_blocking で呼ぶ
+    pub fn flush(&self) {
+        if !self.dirty.swap(false, Ordering::AcqRel) {
+            return;
+        }
+        // 書き込み順が入れ替わらないよう、保存先のロックを持ったまま内容を取り出す
+        let store = self.store.lock().unwrap_or_else(|p| p.into_inner());
+        let Some(store) = store.as_ref() else {
+            return;
+        };
+        let (enabled, items) = {
+            let state = self.lock();
+            let items: Vec<QueueItem> = state
+                .current
+                .values()
+                .chain(state.overlay_current.iter())
+                .map(|active| active.item.clone())
+                .chain(state.items.iter().cloned())
+                .chain(state.overlays.iter().cloned())
+                .collect();
+            (state.config.persistence.enabled, items)
+        };
+        let result = if enabled { store.save(&items) } else { store.remove() };
+        if let Err(e) = result {
+            log::error!("[SpeechQueue] Failed to persist queue: {:#}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::backlog::BacklogConfig;
+    use crate::queue::config::PreemptionConfig;
+    use crate::queue::item::Priority;
+
+    fn request(text: &str, priority: Priority) -> SpeechRequest {
+        SpeechRequest { priority, ..SpeechRequest::new(text) }
+    }
+
+    #[test]
+    fn test_overflow_is_summarized() {
+        let queue = SpeechQueue::new();
+        let backlog = BacklogConfig { max_length: 2, overflow: OverflowPolicy::Summarize, ..BacklogConfig::default() };
+        queue.set_config(QueueConfig { backlog, ..QueueConfig::default() }).unwrap();
+        for text in ["1", "2", "3", "4"] {
+            assert!(matches!(queue.enqueue(request(text, Priority::Normal)), Admission::Queued(_)));
+        }
+
+        let pending: Vec<String> = queue.snapshot().pending.into_iter().map(|i| i.request.text).collect();
+        assert_eq!(pending, ["ほか2件", "3", "4"]);
+    }
+
+    #[test]
+    fn test_pops_by_priority_then_arrival() {
+        let queue = SpeechQueue::new();
+        queue.enqueue(request("low", Priority::Low));
+        queue.enqueue(request("normal-1", Priority::Normal));
+        queue.enqueue(request("high", Priority::High));
+        queue.enqueue(request("normal-2", Priority::Normal));
+
+        let pending: Vec<String> = queue.snapshot().pending.into_iter().map(|i| i.request.text).collect();
+        assert_eq!(pending, ["high", "normal-1", "normal-2", "low"]);
+
+        let mut order = Vec::new();
+        let slot = Slot::Main("chat".to_string());
+        while let Some((item, _)) = queue.pop_next(&slot) {
+            order.push(item.request.text);
+            queue.finish(&slot);
+        }
+        assert_eq!(order, pending);
+        assert!(queue.snapshot().pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_high_priority_preempts_by_policy() {
+        let queue = SpeechQueue::new();
+        queue
+            .set_config(QueueConfig {
+                preemption: PreemptionConfig {
+                    normal: PreemptPolicy::Overlay,
+                    high: PreemptPolicy::InterruptResume,
+                },
+                ..QueueConfig::default()
+            })
+            .unwrap();
+
+        let slot = Slot::Main("chat".to_string());
+        queue.enqueue(request("low", Priority::Low));
+        let (current, control) = queue.pop_next(&slot).unwrap();
+
+        // normal は重ねて読み上げる枠へ、high は読み上げ中の要素を中断させる
+        queue.enqueue(request("normal", Priority::Normal));
+        queue.enqueue(request("high", Priority::High));
+        assert_eq!(control.interrupted().await, Interrupt::Suspend);
+        let snapshot = queue.snapshot();
+        assert_eq!(snapshot.overlay[0].request.text, "normal");
+
+        queue.requeue_suspended(current);
+        queue.finish(&slot);
+        let (next, _) = queue.pop_next(&slot).unwrap();
+        assert_eq!(next.request.text, "high");
+        queue.finish(&slot);
+        let (resumed, _) = queue.pop_next(&slot).unwrap();
+        assert!(resumed.suspended);
+    }
+
+    #[test]
+    fn test_lanes_have_own_queues() {
+        let queue = SpeechQueue::new();
+        queue.enqueue(request("chat", Priority::Normal));
+        queue.enqueue(SpeechRequest { lane: Some("alerts".to_string()), ..request("alert", Priority::Low) });
+        // 設定に無いレーンは既定のレーンに積む
+        queue.enqueue(SpeechRequest { lane: Some("unknown".to_string()), ..request("fallback", Priority::Low) });
+        assert!(queue.check_lane(Some("unknown")).is_err());
+
+        let alerts = Slot::Main("alerts".to_string());
+        let (alert, _) = queue.pop_next(&alerts).unwrap();
+        assert_eq!(alert.request.text, "alert");
+        assert!(queue.pop_next(&alerts).is_none());
+
+        // 別のレーンが読み上げ中でも、レーンごとに読み上げを取り出せる
+        let chat = Slot::Main("chat".to_string());
+        assert_eq!(queue.pop_next(&chat).unwrap().0.request.text, "chat");
+        let snapshot = queue.snapshot();
+        assert_eq!(snapshot.current.iter().map(|i| i.lane()).collect::<Vec<_>>(), ["chat", "alerts"]);
+        assert_eq!(snapshot.pending[0].lane(), "chat");
+        assert_eq!(queue.start_lanes(), ["chat", "alerts", "system"]);
+        assert!(queue.start_lanes().is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+/**
+ * 読み上げの進み具合の記録
+ * 読み上げIDごとに、積まれてから読み上げ終わるまでの状態と時刻を記録して通知する
+ */
+
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+
+use crate::commands::tts::TTSState;
+
+use super::item::{now_millis, QueueItem, Source};
+
+/// 記録を残しておく読み上げの数（古いものから忘れる）
+const MAX_RECORDS: usize = 1000;
+
+/// 読み上げの状態（後ろほど進んでおり、戻ることはない）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeechState {
+    Queued,
+    Synthesizing,
+    Playing,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+impl SpeechState {
+    pub fn is_done(self) -> bool {
+        matches!(self, SpeechState::Finished | SpeechState::Failed | SpeechState::Cancelled)
+    }
+}
+
+/// 読み上げの状態と、各状態になった時刻（UNIXエポックからのミリ秒）
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeechStatus {
+    pub id: String,
+    pub state: SpeechState,
+    pub text: String,
+    pub source: Source,
+    pub lane: Option<String>,
+    pub queued_at: u64,
+    pub synthesizing_at: Option<u64>,
+    pub playing_at: Option<u64>,
+    /// 読み上げ終えた・失敗した・取り消された時刻
+    pub finished_at: Option<u64>,
+    /// 失敗・取り消しの理由
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    records: HashMap<String, SpeechStatus>,
+    order: VecDeque<String>,
+}
+
+/// 読み上げの記録（キュー・読み上げワーカー・HTTPサーバーで共有する）
+#[derive(Clone)]
+pub struct Tracker {
+    state: Arc<Mutex<TrackerState>>,
+    events: broadcast::Sender<SpeechStatus>,
+}
+
+impl Default for Tracker {
+    fn default() -> Self {
+        Self {
+            state: Arc::default(),
+            events: broadcast::channel(256).0,
+        }
+    }
+}
+
+impl Tracker {
+    fn lock(&self) -> std::sync::MutexGuard<'_, TrackerState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 積まれた読み上げを記録する（記録済みなら本文だけを更新する）
+    pub fn track(&self, item: &QueueItem) {
+        let status = {
+            let mut state = self.lock();
+            if let Some(status) = state.records.get_mut(&item.id) {
+                status.text = item.request.text.clone();
+                return;
+            }
+            let status = SpeechStatus {
+                id: item.id.clone(),
+                state: SpeechState::Queued,
+                text: item.request.text.clone(),
+                source: item.request.source,
+                lane: item.request.lane.clone(),
+                queued_at: item.enqueued_at,
+                synthesizing_at: None,
+                playing_at: None,
+                finished_at: None,
+                error: None,
+            };
+            if state.order.len() == MAX_RECORDS {
+                if let Some(oldest) = state.order.pop_front() {
+                    state.records.remove(&oldest);
+                }
+            }
+            state.order.push_back(item.id.clone());
+            state.records.insert(item.id.clone(), status.clone());
+            status
+        };
+        let _ = self.events.send(status);
+    }
+
+    /// 状態を進める（戻る変化と、終わった後の変化は無視する）
+    pub fn advance(&self, id: &str, next: SpeechState) {
+        self.update(id, next, None);
+    }
+
+    /// 失敗・取り消しを理由とともに記録する
+    pub fn fail(&self, id: &str, next: SpeechState, error: impl Into<String>) {
+        self.update(id, next, Some(error.into()));
+    }
+
+    fn update(&self, id: &str, next: SpeechState, error: Option<String>) {
+        let status = {
+            let mut state = self.lock();
+            let Some(status) = state.records.get_mut(id) else {
+                return;
+            };
+            if status.state.is_done() || next <= status.state {
+                return;
+            }
+            let now = now_millis();
+            match next {
+                SpeechState::Queued => {}
+                SpeechState::Synthesizing => status.synthesizing_at = Some(now),
+                SpeechState::Playing => status.playing_at = Some(now),
+                SpeechState::Finished | SpeechState::Failed | SpeechState::Cancelled => {
+                    status.finished_at = Some(now);
+                }
+            }
+            status.state = next;
+            status.error = error;
+            status.clone()
+        };
+        let _ = self.events.send(status);
+    }
+
+    pub fn get(&self, id: &str) -> Option<SpeechStatus> {
+        self.lock().records.get(id).cloned()
+    }
+
+    /// 状態の変化を購読する
+    pub fn subscribe(&self) -> broadcast::Receiver<SpeechStatus> {
+        self.events.subscribe()
+    }
+}
+
+/// 読み上げの状態の変化をフロントエンドへ speech-status として通知する（アプリ起動時に1度だけ呼ぶ）
+pub fn spawn_status_events(app: AppHandle) {
+    let mut events = app.state::<TTSState>().queue.tracker().subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(status) => {
+                    if let Err(e) = app.emit("speech-status", &status) {
+                        log::error!("[SpeechQueue] Failed to emit speech-status event: {}", e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("[SpeechQueue] Skipped {} speech status events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queue::item::SpeechRequest;
+
+    #[test]
+    fn test_state_only_moves_forward() {
+        let tracker = Tracker::default();
+        let item = QueueItem::for_test("a", SpeechRequest::new("こんにちは"));
+        tracker.track(&item);
+        tracker.advance("a", SpeechState::Playing);
+        // 再開などで前の状態を通知されても戻らない
+        tracker.advance("a", SpeechState::Synthesizing);
+        assert_eq!(tracker.get("a").unwrap().state, SpeechState::Playing);
+
+        tracker.fail("a", SpeechState::Cancelled, "停止されました");
+        tracker.advance("a", SpeechState::Finished);
+        let status = tracker.get("a").unwrap();
+        assert_eq!(status.state, SpeechState::Cancelled);
+        assert_eq!(status.error.as_deref(), Some("停止されました"));
+        assert!(status.finished_at.is_some() && status.synthesizing_at.is_none());
+    }
+}
@@ -0,0 +1,326 @@
+/**
+ * 読み上げワーカー
+ * レーンごとにキューから読み上げを1件ずつ取り出し、音声合成から再生完了までを担当する
+ * 割り込みの指示に従って読み上げ中の読み上げを中断・破棄する
+ */
+
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex};
+
+use crate::audio::manager::{AudioManager, PlaybackMonitor};
+use crate::audio::pipeline::{decode_pcm_f32le, UtterancePipeline};
+use crate::audio::playback::{AudioEvent, OVERLAY_LANE};
+use crate::commands::tts::{cancel_synthesis, ensure_audio_manager, resolve_api_key, TTSState};
+use crate::tts::client::CartesiaClient;
+use crate::tts::TTSConfig;
+
+use super::backlog::CatchUp;
+use super::config::LaneConfig;
+use super::item::QueueItem;
+use super::persist::spawn_persister;
+use super::prefetch::{spawn_prefetcher, Buffered};
+use super::speech_queue::{Interrupt, ItemControl, Slot};
+use super::tracker::{spawn_status_events, SpeechState, Tracker};
+
+/// 音声再生スレッドが動いているか確かめる間隔
+const LIVENESS_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 読み上げの結末
+enum Outcome {
+    /// 再生し終えた・停止された
+    Done,
+    /// 割り込みで中断した（続きから読み上げるため積み直す）
+    Suspended,
+}
+
+/// 読み上げワーカーを起動する（アプリ起動時に1度だけ呼ぶ）
+/// レーンごとの通常の読み上げと、重ねて読み上げる割り込みを別々に処理し、次の読み上げを先読みする
+/// レーンが追加されたらそのレーンのワーカーを起動する（消えたレーンのワーカーは自ら終了する）
+pub fn spawn_worker(app: AppHandle) {
+    spawn_status_events(app.clone());
+    spawn_persister(app.state::<TTSState>().queue.clone());
+    spawn_prefetcher(app.clone());
+    spawn_slot(app.clone(), Slot::Overlay);
+    tauri::async_runtime::spawn(async move {
+        let queue = app.state::<TTSState>().queue.clone();
+        loop {
+            for lane in queue.start_lanes() {
+                spawn_slot(app.clone(), Slot::Main(lane));
+            }
+            queue.lanes_changed().await;
+        }
+    });
+}
+
+fn spawn_slot(app: AppHandle, slot: Slot) {
+    tauri::async_runtime::spawn(async move {
+        run(app, slot).await;
+    });
+}
+
+async fn run(app: AppHandle, slot: Slot) {
+    let queue = app.state::<TTSState>().queue.clone();
+    log::info!("[SpeechQueue] {:?} worker started", slot);
+    while let Some((item, control)) = queue.next(&slot).await {
+        let state = app.state::<TTSState>();
+        let outcome = speak(&app, state.inner(), &item, &control, &slot).await;
+        let played = queue.tracker().get(&item.id).is_some_and(|status| status.state == SpeechState::Finished);
+        match outcome {
+            Ok(Outcome::Suspended) => queue.requeue_suspended(item),
+            Ok(Outcome::Done) => {}
+            Err(e) => {
+                log::error!("[SpeechQueue] Failed to speak {}: {}", item.id, e);
+                queue.tracker().fail(&item.id, SpeechState::Failed, e.clone());
+                let _ = app.emit("audio-error", e);
+            }
+        }
+
+        // 最後の読み上げを再生し終えたら、既存のフロントエンド向けに再生完了を通知する（スキップ・停止では通知しない）
+        if queue.finish(&slot) && played {
+            let _ = app.emit("audio-complete", ());
+        }
+    }
+    log::info!("[SpeechQueue] {:?} worker stopped", slot);
+}
+
+/// 1件の読み上げを合成・再生し、再生し終えるか割り込まれるまで待つ
+async fn speak(
+    app: &AppHandle,
+    state: &TTSState,
+    item: &QueueItem,
+    control: &ItemControl,
+    slot: &Slot,
+) -> Result<Outcome, String> {
+    log::info!("[SpeechQueue] Speaking {} on {} ({} chars, resume: {})",
+               item.id, item.lane(), item.request.text.chars().count(), item.suspended);
+    let lane = state.queue.lane_config(item.lane()).unwrap_or_else(|| LaneConfig::new(item.lane()));
+    let config = item.request.apply_to(lane.apply_to(state.config.lock().await.clone()));
+    // 溜まっていれば早口・短縮して追いつく（割り込みは溜まり具合に関係なく通常どおり）
+    let (audio_lane, catch_up) = match slot {
+        Slot::Main(name) => (name.as_str(), state.queue.catch_up(name)),
+        Slot::Overlay => (OVERLAY_LANE, CatchUp::NORMAL),
+    };
+    if catch_up != CatchUp::NORMAL {
+        log::info!("[SpeechQueue] Catching up on {}: speed x{:.2}, max chars {:?}",
+                   audio_lane, catch_up.speed, catch_up.max_chars);
+    }
+    // 先読みした音声があればそれを使う
+    let prefetched = if item.suspended { None } else { state.prefetcher.claim(&item.id) };
+    let api_key = if item.suspended || prefetched.is_some() { None } else { Some(resolve_api_key(state).await?) };
+
+    ensure_audio_manager(state, app).await?;
+    let (mut events, monitor) = {
+        let manager_lock = state.audio_manager.lock().await;
+        let manager = manager_lock
+            .as_ref()
+            .ok_or_else(|| "音声マネージャーが初期化されていません".to_string())?;
+        manager.set_volume(config.volume).map_err(|e| format!("音量設定に失敗しました: {}", e))?;
+        manager.set_speed(config.speed).map_err(|e| format!("速度設定に失敗しました: {}", e))?;
+        manager
+            .set_lane(audio_lane, lane.volume, catch_up.speed)
+            .map_err(|e| format!("レーンの設定に失敗しました: {}", e))?;
+        // 再生完了を取りこぼさないよう、合成・再開の前に購読する
+        let events = manager.subscribe();
+        if item.suspended {
+            manager.resume(&item.id).map_err(|e| e.to_string())?;
+        } else {
+            manager.open_utterance(&item.id, audio_lane).map_err(|e| e.to_string())?;
+        }
+        (events, manager.monitor())
+    };
+
+    let audio = match (prefetched, api_key) {
+        (Some(audio), _) => {
+            log::info!("[SpeechQueue] Using prefetched audio for {}", item.id);
+            Some(audio)
+        }
+        (None, Some(api_key)) => {
+            let (audio_tx, audio_rx) = mpsc::unbounded_channel();
+            let text = catch_up.truncate(&item.request.text);
+            start_synthesis(app, state, item, text, config, api_key, audio_tx).await;
+            Some(audio_rx)
+        }
+        (None, None) => None,
+    };
+    if let Some(audio) = audio {
+        tokio::spawn(feed(state.audio_manager.clone(), item.id.clone(), audio));
+    }
+
+    tokio::select! {
+        result = wait_for_playback(&mut events, &item.id, state.queue.tracker(), &monitor) => {
+            if result.is_err() {
+                cancel_synthesis(state, &item.id).await;
+            }
+            result.map(|()| Outcome::Done)
+        }
+        interrupt = control.interrupted() => {
+            log::info!("[SpeechQueue] {} interrupted: {:?}", item.id, interrupt);
+            let manager_lock = state.audio_manager.lock().await;
+            let manager = manager_lock.as_ref();
+            match interrupt {
+                Interrupt::Cancel => {
+                    drop(manager_lock);
+                    state.queue.tracker().advance(&item.id, SpeechState::Cancelled);
+                    cancel_synthesis(state, &item.id).await;
+                    Ok(Outcome::Done)
+                }
+                Interrupt::Drop => {
+                    if let Some(manager) = manager {
+                        let _ = manager.stop_utterance(&item.id);
+                    }
+                    drop(manager_lock);
+                    state.queue.tracker().fail(&item.id, SpeechState::Cancelled, "優先度の高い読み上げに割り込まれました");
+                    cancel_synthesis(state, &item.id).await;
+                    Ok(Outcome::Done)
+                }
+                Interrupt::Suspend => {
+                    // 合成は止めずに続け、受信した音声は再開まで溜めておく
+                    if let Some(manager) = manager {
+                        let _ = manager.suspend(&item.id);
+                    }
+                    Ok(Outcome::Suspended)
+                }
+            }
+        }
+    }
+}
+
+/// 音声合成を始め、受信した音声をパイプラインに通して送る（合成し終えると閉じる）
+/// 中断された読み上げ・先読みも合成を続けるため、読み上げとは別のタスクで動かす
+pub(super) async fn start_synthesis(
+    app: &AppHandle,
+    state: &TTSState,
+    item: &QueueItem,
+    text: String,
+    config: TTSConfig,
+    api_key: String,
+    samples_tx: mpsc::UnboundedSender<Vec<f32>>,
+) {
+    let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
+    state.synthesis_cancels.lock().await.insert(item.id.clone(), cancel_tx);
+    let tracker = state.queue.tracker().clone();
+    tracker.advance(&item.id, SpeechState::Synthesizing);
+
+    let audio_config = state.audio_config.lock().await.clone();
+    let mut pipeline =
+        UtterancePipeline::new(&item.request.effects_for(&config), &audio_config).with_gain(item.request.gain());
+    let client = CartesiaClient::new_with_api_key(config, api_key);
+    let prefetcher = state.prefetcher.clone();
+    let synthesis_cancels = state.synthesis_cancels.clone();
+    let app = app.clone();
+    let id = item.id.clone();
+
+    tokio::spawn(async move {
+        let (audio_tx, mut audio_rx) = mpsc::channel::<Vec<u8>>(100);
+        let synthesis = client.synthesize_speech(&text, audio_tx, cancel_rx);
+        let process = async {
+            // 送り先が無くなった（先読みをやめた）後も、合成が止まるまで受信は続ける
+            let mut discarded = false;
+            while let Some(audio_data) = audio_rx.recv().await {
+                if discarded {
+                    continue;
+                }
+                let samples = pipeline.process(decode_pcm_f32le(&audio_data));
+                if prefetcher.account(&id, samples.len() * std::mem::size_of::<f32>()) == Buffered::OverLimit {
+                    log::warn!("[SpeechQueue] Prefetch memory limit reached, abandoning {}", id);
+                    if let Some(cancel) = synthesis_cancels.lock().await.remove(&id) {
+                        let _ = cancel.send(());
+                    }
+                    discarded = true;
+                    continue;
+                }
+                discarded = samples_tx.send(samples).is_err();
+            }
+            if !discarded {
+                let mut tail = pipeline.finish();
+                tail.extend(pipeline.inter_utterance_gap());
+                let _ = samples_tx.send(tail);
+            }
+            drop(samples_tx);
+        };
+        let (result, ()) = tokio::join!(synthesis, process);
+        synthesis_cancels.lock().await.remove(&id);
+
+        if let Err(e) = result {
+            // 先読みの失敗は、順番が来てから合成し直すときに改めて知らせる
+            if prefetcher.abandon(&id) {
+                log::warn!("[SpeechQueue] Prefetch failed for {}: {}", id, e);
+            } else {
+                log::error!("[SpeechQueue] Synthesis failed for {}: {}", id, e);
+                tracker.fail(&id, SpeechState::Failed, format!("音声合成に失敗しました: {}", e));
+                let _ = app.emit("audio-error", format!("音声合成に失敗しました: {}", e));
+            }
+        }
+    });
+}
+
+/// 合成した音声をAudioManagerへ送り、合成し終えたら発話を閉じる
+async fn feed(
+    audio_manager: Arc<Mutex<Option<AudioManager>>>,
+    id: String,
+    mut samples_rx: mpsc::UnboundedReceiver<Vec<f32>>,
+) {
+    while let Some(samples) = samples_rx.recv().await {
+        if samples.is_empty() {
+            continue;
+        }
+        let manager_lock = audio_manager.lock().await;
+        if let Some(manager) = manager_lock.as_ref() {
+            if let Err(e) = manager.play_audio(&id, samples) {
+                log::error!("[SpeechQueue] Failed to send audio: {}", e);
+            }
+        }
+    }
+
+    // 再生完了はAudioManagerが実際に再生し終えた時点でplayback-finishedとして通知される
+    let manager_lock = audio_manager.lock().await;
+    if let Some(manager) = manager_lock.as_ref() {
+        if let Err(e) = manager.finish_utterance(&id) {
+            log::error!("[SpeechQueue] Failed to finish utterance: {}", e);
+        }
+    }
+}
+
+/// 発話の再生が終わるまで待ち、再生の始まりと終わりを記録する
+/// 音声再生スレッドが止まった場合はエラーを返す
+async fn wait_for_playback(
+    events: &mut broadcast::Receiver<AudioEvent>,
+    utterance_id: &str,
+    tracker: &Tracker,
+    monitor: &PlaybackMonitor,
+) -> Result<(), String> {
+    let mut liveness = tokio::time::interval(LIVENESS_CHECK_INTERVAL);
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(AudioEvent::Started { utterance_id: id }) if id == utterance_id => {
+                    tracker.advance(utterance_id, SpeechState::Playing);
+                }
+                Ok(AudioEvent::Finished { utterance_id: id, cancelled }) if id == utterance_id => {
+                    let done = if cancelled { SpeechState::Cancelled } else { SpeechState::Finished };
+                    tracker.advance(utterance_id, done);
+                    return Ok(());
+                }
+                Ok(_) => {}
+                // 再生完了の通知を読み飛ばした可能性があるため、発話が残っているかで判断する
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    if !monitor.has_utterance(utterance_id) {
+                        log::warn!("[SpeechQueue] Missed the end of {} after skipping {} events", utterance_id, skipped);
+                        tracker.advance(utterance_id, SpeechState::Finished);
+                        return Ok(());
+                    }
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    return Err("音声出力が停止しました".to_string());
+                }
+            },
+            _ = liveness.tick() => {
+                if !monitor.is_alive() {
+                    return Err("音声出力スレッドが停止しました".to_string());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,322 @@
+/**
+ * iCalendar（.ics）の読み込み
+ * 予定表の予定に設定されたアラーム（VALARM）を、読み上げる時刻と本文として取り出す
+ */
+
+use chrono::{NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+
+use super::job::from_local;
+
+/// 予定のアラーム
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcsAlarm {
+    /// 予定のUIDとアラームの番号（取り込み直したときに同じアラームを見分ける）
+    pub origin: String,
+    /// 予定の件名（無ければアラームの説明）
+    pub text: String,
+    pub at: u64,
+}
+
+/// 予定表から取り出したアラーム
+#[derive(Debug, Default)]
+pub struct IcsAlarms {
+    pub alarms: Vec<IcsAlarm>,
+    /// 予定の時刻のタイムゾーン（TZID）を解釈できず、時刻を決められなかったアラームの数
+    pub unknown_timezone: usize,
+}
+
+/// 内容行（名前・パラメーター・値）
+struct Property<'a> {
+    name: String,
+    params: Vec<(String, &'a str)>,
+    value: &'a str,
+}
+
+impl Property<'_> {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.trim_matches('"'))
+    }
+}
+
+#[derive(Default)]
+struct Alarm {
+    trigger: Option<(String, bool, bool)>,
+    description: Option<String>,
+}
+
+#[derive(Default)]
+struct Event {
+    uid: Option<String>,
+    summary: Option<String>,
+    start: Option<u64>,
+    end: Option<u64>,
+    /// 開始・終了の時刻に解釈できないTZIDが指定されていた
+    unknown_timezone: bool,
+    alarms: Vec<Alarm>,
+}
+
+/// 折り返された行（先頭が空白・タブ）をつなげる
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// 内容行を分解する（引用符の中の ':' と ';' は区切りとみなさない）
+fn parse_property(line: &str) -> Option<Property<'_>> {
+    let mut quoted = false;
+    let mut separators = Vec::new();
+    let mut value_start = None;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => separators.push(index),
+            ':' if !quoted => {
+                value_start = Some(index);
+                break;
+            }
+            _ => {}
+        }
+    }
+    let value_start = value_start?;
+    let name_end = separators.first().copied().unwrap_or(value_start);
+    let params = separators
+        .iter()
+        .zip(separators.iter().skip(1).chain(Some(&value_start)))
+        .filter_map(|(&start, &end)| line[start + 1..end].split_once('='))
+        .map(|(key, value)| (key.to_ascii_uppercase(), value))
+        .collect();
+    Some(Property {
+        name: line[..name_end].to_ascii_uppercase(),
+        params,
+        value: &line[value_start + 1..],
+    })
+}
+
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => unescaped.push(' '),
+            Some(other) => unescaped.push(other),
+            None => {}
+        }
+    }
+    unescaped.trim().to_string()
+}
+
+/// 日付・日時を解釈する（末尾がZならUTC, TZIDの指定があればそのタイムゾーン, それ以外はローカル時刻とみなす）
+fn parse_datetime(value: &str, timezone: Option<Tz>) -> Option<u64> {
+    let value = value.trim();
+    if value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return from_local(date.and_hms_opt(0, 0, 0)?);
+    }
+    match (value.strip_suffix('Z'), timezone) {
+        (Some(utc), _) => {
+            let datetime = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+            u64::try_from(datetime.and_utc().timestamp_millis()).ok()
+        }
+        (None, Some(timezone)) => {
+            let datetime = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+            let at = timezone.from_local_datetime(&datetime).earliest()?;
+            u64::try_from(at.timestamp_millis()).ok()
+        }
+        (None, None) => from_local(NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?),
+    }
+}
+
+/// 予定の開始・終了の時刻を解釈する（TZIDを解釈できなければ印を付けて時刻を決めない）
+fn parse_event_time(property: &Property, event: &mut Event) -> Option<u64> {
+    let timezone = match property.param("TZID") {
+        Some(tzid) => match tzid.parse::<Tz>() {
+            Ok(timezone) => Some(timezone),
+            Err(_) => {
+                event.unknown_timezone = true;
+                return None;
+            }
+        },
+        None => None,
+    };
+    parse_datetime(property.value, timezone)
+}
+
+/// 期間（例: -PT15M, P1DT2H, P1W）をミリ秒にする
+fn parse_duration(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (sign, value) = match value.as_bytes().first()? {
+        b'-' => (-1, &value[1..]),
+        b'+' => (1, &value[1..]),
+        _ => (1, value),
+    };
+    let mut rest = value.strip_prefix('P')?;
+    let mut total: i64 = 0;
+    let mut in_time = false;
+    while !rest.is_empty() {
+        if let Some(time) = rest.strip_prefix('T') {
+            in_time = true;
+            rest = time;
+            continue;
+        }
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let amount: i64 = rest[..digits].parse().ok()?;
+        let unit_secs = match (rest.as_bytes()[digits], in_time) {
+            (b'W', false) => 7 * 24 * 60 * 60,
+            (b'D', false) => 24 * 60 * 60,
+            (b'H', true) => 60 * 60,
+            (b'M', true) => 60,
+            (b'S', true) => 1,
+            _ => return None,
+        };
+        total += amount * unit_secs * 1000;
+        rest = &rest[digits + 1..];
+    }
+    Some(sign * total)
+}
+
+/// 予定のアラームを取り出す（時刻を決められないアラームは飛ばす）
+/// 繰り返しの予定（RRULE）は最初の1回だけを扱う
+pub fn parse_alarms(content: &str) -> Result<IcsAlarms, String> {
+    let lines = unfold(content);
+    if !lines.iter().any(|line| line.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err("iCalendar形式ではありません".to_string());
+    }
+
+    let mut alarms = IcsAlarms::default();
+    let mut event: Option<Event> = None;
+    let mut alarm: Option<Alarm> = None;
+    for line in &lines {
+        let Some(property) = parse_property(line) else {
+            continue;
+        };
+        let value = property.value;
+        match (property.name.as_str(), event.as_mut(), alarm.as_mut()) {
+            ("BEGIN", _, _) if value.eq_ignore_ascii_case("VEVENT") => event = Some(Event::default()),
+            ("BEGIN", Some(_), _) if value.eq_ignore_ascii_case("VALARM") => alarm = Some(Alarm::default()),
+            ("END", Some(event), Some(_)) if value.eq_ignore_ascii_case("VALARM") => {
+                event.alarms.extend(alarm.take());
+            }
+            ("END", Some(_), None) if value.eq_ignore_ascii_case("VEVENT") => {
+                if let Some(event) = event.take() {
+                    resolve_alarms(event, &mut alarms);
+                }
+            }
+            ("TRIGGER", Some(_), Some(alarm)) => {
+                let absolute = property.param("VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE-TIME"));
+                let from_end = property.param("RELATED").is_some_and(|v| v.eq_ignore_ascii_case("END"));
+                alarm.trigger = Some((value.to_string(), absolute, from_end));
+            }
+            ("DESCRIPTION", Some(_), Some(alarm)) => alarm.description = Some(unescape(value)),
+            ("UID", Some(event), None) => event.uid = Some(value.trim().to_string()),
+            ("SUMMARY", Some(event), None) => event.summary = Some(unescape(value)),
+            ("DTSTART", Some(event), None) => event.start = parse_event_time(&property, event),
+            ("DTEND", Some(event), None) => event.end = parse_event_time(&property, event),
+            _ => {}
+        }
+    }
+    Ok(alarms)
+}
+
+fn resolve_alarms(event: Event, found: &mut IcsAlarms) {
+    let uid = event.uid.clone().unwrap_or_default();
+    let alarms = event
+        .alarms
+        .iter()
+        .enumerate()
+        .filter_map(|(index, alarm)| {
+            let (trigger, absolute, from_end) = alarm.trigger.as_ref()?;
+            let at = if *absolute {
+                parse_datetime(trigger, None)?
+            } else {
+                if event.unknown_timezone {
+                    found.unknown_timezone += 1;
+                    return None;
+                }
+                let base = if *from_end { event.end.or(event.start) } else { event.start }?;
+                u64::try_from(base as i64 + parse_duration(trigger)?).ok()?
+            };
+            let text = event
+                .summary
+                .clone()
+                .filter(|summary| !summary.is_empty())
+                .or_else(|| alarm.description.clone())
+                .filter(|text| !text.is_empty())?;
+            Some(IcsAlarm { origin: format!("{}#{}", uid, index), text, at })
+        })
+        .collect::<Vec<_>>();
+    found.alarms.extend(alarms);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_event_alarms() {
+        let content = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+BEGIN:VEVENT\r\n\
+UID:event-1\r\n\
+SUMMARY:定例\\, 配信の\r\n  打ち合わせ\r\n\
+DTSTART:20250115T120000Z\r\n\
+DTEND:20250115T130000Z\r\n\
+BEGIN:VALARM\r\n\
+ACTION:DISPLAY\r\n\
+TRIGGER:-PT15M\r\n\
+END:VALARM\r\n\
+BEGIN:VALARM\r\n\
+TRIGGER;RELATED=END:PT0S\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:no-alarm\r\n\
+SUMMARY:アラームなし\r\n\
+DTSTART:20250115T150000Z\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:tokyo\r\n\
+SUMMARY:東京時間\r\n\
+DTSTART;TZID=Asia/Tokyo:20250115T210000\r\n\
+BEGIN:VALARM\r\n\
+TRIGGER:PT0S\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:windows\r\n\
+SUMMARY:解釈できないタイムゾーン\r\n\
+DTSTART;TZID=Tokyo Standard Time:20250115T210000\r\n\
+BEGIN:VALARM\r\n\
+TRIGGER:PT0S\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+        let IcsAlarms { alarms, unknown_timezone } = parse_alarms(content).unwrap();
+        let noon = 1_736_942_400_000;
+        assert_eq!(alarms.len(), 3);
+        assert_eq!(alarms[0].text, "定例, 配信の 打ち合わせ");
+        assert_eq!(alarms[0].at, noon - 15 * 60 * 1000);
+        assert_eq!(alarms[1].origin, "event-1#1");
+        assert_eq!(alarms[1].at, noon + 60 * 60 * 1000);
+        // Asia/Tokyo の21時はUTCの12時
+        assert_eq!(alarms[2].at, noon);
+        assert_eq!(unknown_timezone, 1);
+
+        assert_eq!(parse_duration("P1DT2H"), Some((24 + 2) * 60 * 60 * 1000));
+        assert!(parse_alarms("not a calendar").is_err());
+    }
+}
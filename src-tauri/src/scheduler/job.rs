@@ -0,0 +1,214 @@
+/**
+ * 予約読み上げの予定
+ * 1回限り・繰り返しの予定と、次に読み上げる時刻の計算を担当する
+ */
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::queue::item::{now_millis, SpeechRequest};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// 読み上げる時刻の決め方（時刻はすべてPCのローカル時刻で扱う）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Schedule {
+    /// 1回限り（UNIXエポックからのミリ秒, "21:00" や "2025-01-01T21:00" の表記も受け付ける）
+    Once {
+        #[serde(deserialize_with = "deserialize_at")]
+        at: u64,
+    },
+    /// 0時から数えて `every_minutes` 分ごと（`offset_minutes` 分ずらす）
+    /// 60分ごとなら毎時0分、1440分ごとに offset 1260 なら毎日21:00
+    Interval {
+        every_minutes: u32,
+        #[serde(default)]
+        offset_minutes: u32,
+    },
+}
+
+impl Schedule {
+    pub fn validate(&self) -> Result<(), String> {
+        if let Schedule::Interval { every_minutes, offset_minutes } = *self {
+            if every_minutes == 0 || !MINUTES_PER_DAY.is_multiple_of(every_minutes) {
+                return Err("繰り返しの間隔は1日（1440分）を割り切れる分数で指定してください".to_string());
+            }
+            if offset_minutes >= MINUTES_PER_DAY {
+                return Err("ずらす分数は1440分未満で指定してください".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// `after` より後で最初に読み上げる時刻（1回限りで過ぎていればNone）
+    pub fn next_after(&self, after: u64) -> Option<u64> {
+        match *self {
+            Schedule::Once { at } => (at > after).then_some(at),
+            Schedule::Interval { every_minutes, offset_minutes } => {
+                next_interval(every_minutes, offset_minutes, after)
+            }
+        }
+    }
+}
+
+/// 0時から数えて `every` 分ごとの枠のうち、`after` より後の最初の枠
+fn next_interval(every: u32, offset: u32, after: u64) -> Option<u64> {
+    let after_local = local(after)?;
+    let offset = offset % every;
+    let minute_of_day = after_local.hour() * 60 + after_local.minute();
+    let mut date = after_local.date_naive();
+    let mut slot = if minute_of_day < offset {
+        offset
+    } else {
+        offset + ((minute_of_day - offset) / every + 1) * every
+    };
+    // 夏時間の切り替えで存在しない時刻は飛ばす
+    for _ in 0..=2 * MINUTES_PER_DAY / every {
+        if slot >= MINUTES_PER_DAY {
+            date = date.succ_opt()?;
+            slot = offset;
+        }
+        let time = NaiveTime::from_hms_opt(slot / 60, slot % 60, 0)?;
+        if let Some(at) = from_local(date.and_time(time)) {
+            if at > after {
+                return Some(at);
+            }
+        }
+        slot += every;
+    }
+    None
+}
+
+fn local(millis: u64) -> Option<DateTime<Local>> {
+    Local.timestamp_millis_opt(millis as i64).single()
+}
+
+/// ローカル時刻をUNIXエポックからのミリ秒にする（夏時間で重なる時刻は早いほう）
+pub fn from_local(datetime: NaiveDateTime) -> Option<u64> {
+    let at = Local.from_local_datetime(&datetime).earliest()?;
+    u64::try_from(at.timestamp_millis()).ok()
+}
+
+/// 時刻の表記を解釈する
+/// "21:00"（次に来るその時刻）・"2025-01-01T21:00"（ローカル時刻）・RFC 3339
+pub fn parse_time(value: &str, now: u64) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return u64::try_from(at.timestamp_millis()).ok();
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(value, format) {
+            return from_local(datetime);
+        }
+    }
+    let time = NaiveTime::parse_from_str(value, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
+        .ok()?;
+    let today: NaiveDate = local(now)?.date_naive();
+    let at = from_local(today.and_time(time))?;
+    if at > now {
+        Some(at)
+    } else {
+        from_local(today.succ_opt()?.and_time(time))
+    }
+}
+
+fn deserialize_at<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum At {
+        Millis(u64),
+        Text(String),
+    }
+    match At::deserialize(deserializer)? {
+        At::Millis(at) => Ok(at),
+        At::Text(text) => parse_time(&text, now_millis())
+            .ok_or_else(|| D::Error::custom(format!("時刻を解釈できません: {}", text))),
+    }
+}
+
+/// 本文の {hour}・{minute} を読み上げる時刻に置き換える（時報など）
+pub fn render(text: &str, at: u64) -> String {
+    match local(at) {
+        Some(at) => text
+            .replace("{hour}", &at.hour().to_string())
+            .replace("{minute}", &at.minute().to_string()),
+        None => text.to_string(),
+    }
+}
+
+/// 予約した読み上げ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    pub id: String,
+    /// 表示名
+    #[serde(default)]
+    pub name: Option<String>,
+    pub schedule: Schedule,
+    /// 読み上げる内容
+    pub request: SpeechRequest,
+    pub enabled: bool,
+    /// 次に読み上げる時刻（無効ならNone）
+    pub next_at: Option<u64>,
+    /// 最後に読み上げた時刻
+    #[serde(default)]
+    pub last_at: Option<u64>,
+    /// 取り込んだ予定表のアラーム（iCalendarのUIDとアラームの番号）
+    #[serde(default)]
+    pub origin: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 読み上げの予約（Tauriコマンド・HTTP向け）
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewSchedule {
+    #[serde(default)]
+    pub name: Option<String>,
+    pub schedule: Schedule,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// 本文・優先度・声など（本文の {hour}・{minute} は読み上げる時刻に置き換える）
+    #[serde(flatten)]
+    pub request: SpeechRequest,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> u64 {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+        from_local(date.and_hms_opt(hour, minute, 0).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_interval_aligns_to_local_clock() {
+        let hourly = Schedule::Interval { every_minutes: 60, offset_minutes: 0 };
+        assert_eq!(hourly.next_after(at(10, 15)), Some(at(11, 0)));
+        // ちょうどの時刻に読み上げた後は次の枠
+        assert_eq!(hourly.next_after(at(11, 0)), Some(at(12, 0)));
+
+        let half_hourly = Schedule::Interval { every_minutes: 30, offset_minutes: 0 };
+        assert_eq!(half_hourly.next_after(at(10, 15)), Some(at(10, 30)));
+
+        let daily = Schedule::Interval { every_minutes: MINUTES_PER_DAY, offset_minutes: 21 * 60 };
+        assert_eq!(daily.next_after(at(22, 0)), Some(at(21, 0) + 24 * 60 * 60 * 1000));
+        assert!(Schedule::Interval { every_minutes: 7, offset_minutes: 0 }.validate().is_err());
+    }
+
+    #[test]
+    fn test_parse_time_and_render() {
+        assert_eq!(parse_time("21:00", at(10, 0)), Some(at(21, 0)));
+        assert_eq!(parse_time("09:00", at(10, 0)), Some(at(9, 0) + 24 * 60 * 60 * 1000));
+        assert_eq!(parse_time("2025-01-15T21:00", 0), Some(at(21, 0)));
+        assert_eq!(parse_time("2025-01-15T12:00:00Z", 0), Some(1_736_942_400_000));
+        assert_eq!(parse_time("tomorrow", 0), None);
+
+        assert_eq!(render("{hour}時{minute}分です", at(21, 5)), "21時5分です");
+    }
+}
@@ -0,0 +1,345 @@
+/**
+ * 予約読み上げモジュール
+ * 1回限り・繰り返し・予定表（.ics）のアラームを、時刻になったら読み上げキューに積む
+ */
+
+pub mod ics;
+pub mod job;
+
+pub use job::{NewSchedule, ScheduledJob};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Notify;
+
+use crate::commands::tts::TTSState;
+use crate::queue::item::now_millis;
+use crate::queue::persist::write_atomic;
+use crate::queue::{Admission, Source, SpeechRequest};
+use crate::tts::storage::app_data_dir;
+use job::{render, Schedule};
+
+const SCHEDULE_FILE: &str = "schedules.json";
+/// 予定の時刻からこれ以上遅れた読み上げは飛ばす（スリープからの復帰時など）
+const MISSED_GRACE_MS: u64 = 2 * 60 * 1000;
+/// 時計の変更に追従するため、予定が先でもこの間隔で見直す
+const MAX_SLEEP_MS: u64 = 60 * 1000;
+
+/// 時刻になった予約
+#[derive(Debug, Clone)]
+pub struct Fired {
+    pub job_id: String,
+    pub request: SpeechRequest,
+}
+
+/// 予定表の取り込み結果
+#[derive(Debug, Clone, Serialize)]
+pub struct IcsImport {
+    pub imported: usize,
+    /// 時刻を過ぎていたため取り込まなかったアラーム
+    pub skipped: usize,
+    /// タイムゾーンを解釈できず取り込まなかったアラーム
+    pub unknown_timezone: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PersistedSchedules {
+    jobs: Vec<ScheduledJob>,
+}
+
+/// Tauriコマンド・HTTPサーバー・実行タスクで共有する予約
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    jobs: Arc<Mutex<Vec<ScheduledJob>>>,
+    changed: Arc<Notify>,
+    /// 保存先（未設定ならディスクに保存しない）
+    path: Arc<Mutex<Option<PathBuf>>>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<ScheduledJob>> {
+        self.jobs.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// 予約の一覧（次に読み上げる順, 無効な予約は最後）
+    pub fn list(&self) -> Vec<ScheduledJob> {
+        let mut jobs = self.lock().clone();
+        jobs.sort_by_key(|job| job.next_at.unwrap_or(u64::MAX));
+        jobs
+    }
+
+    pub fn add(&self, new: NewSchedule) -> Result<ScheduledJob, String> {
+        if new.request.text.trim().is_empty() {
+            return Err("テキストが空です".to_string());
+        }
+        new.schedule.validate()?;
+        let next_at = new.schedule.next_after(now_millis());
+        if next_at.is_none() {
+            return Err("過ぎた時刻は予約できません".to_string());
+        }
+        let job = ScheduledJob {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: new.name,
+            schedule: new.schedule,
+            request: SpeechRequest { source: Source::Schedule, ..new.request },
+            enabled: new.enabled,
+            next_at: next_at.filter(|_| new.enabled),
+            last_at: None,
+            origin: None,
+        };
+        log::info!("[Scheduler] Added {} ({:?})", job.id, job.schedule);
+        self.lock().push(job.clone());
+        self.save();
+        Ok(job)
+    }
+
+    pub fn remove(&self, id: &str) -> bool {
+        let removed = {
+            let mut jobs = self.lock();
+            let before = jobs.len();
+            jobs.retain(|job| job.id != id);
+            jobs.len() != before
+        };
+        if removed {
+            self.save();
+        }
+        removed
+    }
+
+    pub fn set_enabled(&self, id: &str, enabled: bool) -> Result<ScheduledJob, String> {
+        let job = {
+            let mut jobs = self.lock();
+            let job = jobs
+                .iter_mut()
+                .find(|job| job.id == id)
+                .ok_or_else(|| "予約が見つかりません".to_string())?;
+            let next_at = job.schedule.next_after(now_millis());
+            if enabled && next_at.is_none() {
+                return Err("過ぎた時刻は予約できません".to_string());
+            }
+            job.enabled = enabled;
+            job.next_at = next_at.filter(|_| enabled);
+            job.clone()
+        };
+        self.save();
+        Ok(job)
+    }
+
+    /// 予定表のアラームを1回限りの予約として取り込む（取り込み済みのアラームは置き換える）
+    pub fn import_ics(&self, content: &str) -> Result<IcsImport, String> {
+        let parsed = ics::parse_alarms(content)?;
+        let now = now_millis();
+        let (upcoming, past): (Vec<_>, Vec<_>) = parsed.alarms.into_iter().partition(|alarm| alarm.at > now);
+        let report = IcsImport {
+            imported: upcoming.len(),
+            skipped: past.len(),
+            unknown_timezone: parsed.unknown_timezone,
+        };
+        {
+            let mut jobs = self.lock();
+            jobs.retain(|job| {
+                job.origin
+                    .as_ref()
+                    .is_none_or(|origin| !upcoming.iter().any(|alarm| &alarm.origin == origin))
+            });
+            jobs.extend(upcoming.into_iter().map(|alarm| ScheduledJob {
+                id: uuid::Uuid::new_v4().to_string(),
+                name: None,
+                schedule: Schedule::Once { at: alarm.at },
+                request: SpeechRequest { source: Source::Schedule, ..SpeechRequest::new(alarm.text) },
+                enabled: true,
+                next_at: Some(alarm.at),
+                last_at: None,
+                origin: Some(alarm.origin),
+            }));
+        }
+        log::info!(
+            "[Scheduler] Imported {} alarms ({} past, {} with unknown time zone)",
+            report.imported,
+            report.skipped,
+            report.unknown_timezone
+        );
+        self.save();
+        Ok(report)
+    }
+
+    /// 時刻になった予約を取り出し、次の時刻へ進める（1回限りの予約は取り除く）
+    pub fn take_due(&self, now: u64) -> Vec<Fired> {
+        let mut fired = Vec::new();
+        let removed = {
+            let mut jobs = self.lock();
+            for job in jobs.iter_mut() {
+                let Some(next_at) = job.next_at.filter(|at| *at <= now) else {
+                    continue;
+                };
+                if now - next_at > MISSED_GRACE_MS {
+                    log::warn!("[Scheduler] Missed {} scheduled at {}", job.id, next_at);
+                } else {
+                    let request = SpeechRequest {
+                        text: render(&job.request.text, next_at),
+                        ..job.request.clone()
+                    };
+                    fired.push(Fired { job_id: job.id.clone(), request });
+                    job.last_at = Some(next_at);
+                }
+                job.next_at = job.schedule.next_after(now);
+            }
+            // 読み上げた・読み上げそこねた1回限りの予約は取り除く
+            let before = jobs.len();
+            jobs.retain(|job| {
+                !matches!(job.schedule, Schedule::Once { .. }) || job.next_at.is_some() || !job.enabled
+            });
+            jobs.len() != before
+        };
+        if !fired.is_empty() || removed {
+            self.save();
+        }
+        fired
+    }
+
+    /// 次に見直すまでの時間
+    fn next_wake(&self, now: u64) -> Duration {
+        let next = self.lock().iter().filter_map(|job| job.next_at).min();
+        let wait = next.map_or(MAX_SLEEP_MS, |at| at.saturating_sub(now).min(MAX_SLEEP_MS));
+        Duration::from_millis(wait)
+    }
+
+    /// 保存先を設定し、保存された予約を読み込む（起動時に1度だけ呼ぶ）
+    /// 繰り返しの予約は、止まっていた間の分を飛ばして次の時刻から読み上げる
+    pub fn restore(&self, path: PathBuf) -> Result<usize> {
+        let jobs = if path.exists() {
+            let bytes = std::fs::read(&path).with_context(|| format!("{:?} の読み込みに失敗しました", path))?;
+            let persisted: PersistedSchedules =
+                serde_json::from_slice(&bytes).context("保存された予約が壊れています")?;
+            persisted.jobs
+        } else {
+            Vec::new()
+        };
+        let now = now_millis();
+        let count = jobs.len();
+        {
+            let mut current = self.lock();
+            current.extend(jobs.into_iter().map(|mut job| {
+                if job.enabled && matches!(job.schedule, Schedule::Interval { .. }) {
+                    job.next_at = job.schedule.next_after(now);
+                }
+                job
+            }));
+        }
+        *self.path.lock().unwrap_or_else(|p| p.into_inner()) = Some(path);
+        self.changed.notify_one();
+        Ok(count)
+    }
+
+    /// アプリケーションのデータディレクトリから予約を読み込む
+    pub fn restore_default(&self) -> Result<usize> {
+        let dir = app_data_dir().map_err(|e| anyhow!("{}", e))?;
+        self.restore(dir.join(SCHEDULE_FILE))
+    }
+
+    /// 予約が変わるまで待つ
+    async fn changed(&self) {
+        self.changed.notified().await;
+    }
+
+    /// 予約を保存し、実行タスクに見直させる
+    fn save(&self) {
+        self.changed.notify_one();
+        let path = self.path.lock().unwrap_or_else(|p| p.into_inner());
+        let Some(path) = path.as_ref() else {
+            return;
+        };
+        let persisted = PersistedSchedules { jobs: self.lock().clone() };
+        let result = serde_json::to_vec_pretty(&persisted)
+            .context("予約のシリアライズに失敗しました")
+            .and_then(|bytes| write_atomic(path, &bytes));
+        if let Err(e) = result {
+            log::error!("[Scheduler] Failed to save schedules: {:#}", e);
+        }
+    }
+}
+
+/// フロントエンドへ通知する予約の読み上げ
+#[derive(Debug, Serialize)]
+struct FiredNotice<'a> {
+    job_id: &'a str,
+    id: &'a str,
+    text: &'a str,
+}
+
+/// 時刻になった予約を読み上げキューに積むタスクを起動する（アプリ起動時に1度だけ呼ぶ）
+pub fn spawn_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let scheduler = app.state::<Scheduler>().inner().clone();
+        log::info!("[Scheduler] Started");
+        loop {
+            let now = now_millis();
+            for fired in scheduler.take_due(now) {
+                let queue = &app.state::<TTSState>().queue;
+                match queue.enqueue(fired.request) {
+                    Admission::Queued(item) => {
+                        log::info!("[Scheduler] Enqueued {} for {}", item.id, fired.job_id);
+                        let notice = FiredNotice { job_id: &fired.job_id, id: &item.id, text: &item.request.text };
+                        if let Err(e) = app.emit("schedule-fired", &notice) {
+                            log::error!("[Scheduler] Failed to emit schedule-fired event: {}", e);
+                        }
+                    }
+                    admission => log::warn!("[Scheduler] {} was not enqueued: {:?}", fired.job_id, admission),
+                }
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(scheduler.next_wake(now)) => {}
+                _ = scheduler.changed() => {}
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_due_fires_and_advances() {
+        let scheduler = Scheduler::new();
+        let now = now_millis();
+        let once = scheduler
+            .add(NewSchedule {
+                name: None,
+                schedule: Schedule::Once { at: now + 1_000 },
+                enabled: true,
+                request: SpeechRequest::new("一度だけ"),
+            })
+            .unwrap();
+        let hourly = scheduler
+            .add(NewSchedule {
+                name: None,
+                schedule: Schedule::Interval { every_minutes: 60, offset_minutes: 0 },
+                enabled: true,
+                request: SpeechRequest::new("{hour}時です"),
+            })
+            .unwrap();
+        assert!(scheduler.take_due(now).is_empty());
+
+        let fired = scheduler.take_due(now + 1_000);
+        let fired = fired.iter().find(|fired| fired.job_id == once.id).unwrap();
+        assert_eq!(fired.request.source, Source::Schedule);
+        // 1回限りの予約は読み上げたら消える
+        let jobs = scheduler.list();
+        assert_eq!(jobs.len(), 1);
+
+        // 大きく遅れた読み上げは飛ばし、次の時刻へ進める
+        let hourly_at = jobs[0].next_at.unwrap();
+        assert!(scheduler.take_due(hourly_at + MISSED_GRACE_MS + 1).is_empty());
+        assert!(scheduler.list()[0].next_at.unwrap() > hourly_at);
+        assert_eq!(scheduler.list()[0].id, hourly.id);
+    }
+}
@@ -98,7 +98,8 @@ impl CartesiaClient {
             voice: VoiceConfig {
                 mode: "id".to_string(),
                 id: self.config.voice_id.clone(),
-                speed: Some(format!("{:.1}", self.config.speed)),
+                // 再生速度はローカルのタイムストレッチで変えるため、合成は常に等速で行う
+                speed: None,
                 experimental_controls: if self.config.voice_speed != 0.0 {
                     Some(ExperimentalControls {
                         speed: Some(format_voice_speed(self.config.voice_speed)),
@@ -238,7 +239,10 @@ mod tests {
         assert_eq!(config.speed, 1.5);
 
         let config = TTSConfig::new().with_speed(3.0);
-        assert_eq!(config.speed, 2.0); // Clamped to max
+        assert_eq!(config.speed, 3.0);
+
+        let config = TTSConfig::new().with_speed(4.0);
+        assert_eq!(config.speed, 3.0); // Clamped to max
 
         let config = TTSConfig::new().with_speed(0.1);
         assert_eq!(config.speed, 0.5); // Clamped to min
@@ -5,31 +5,41 @@
 
 use keyring::Entry;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
+use crate::audio::effects::EffectConfig;
+use crate::audio::stretch::clamp_speed;
 use super::error::{TTSError, TTSResult};
 
 const SERVICE_NAME: &str = "Taneyomi-kun";
 const API_KEY_NAME: &str = "cartesia_api_key";
 
+// 言語ごとの既定の声（フロントエンドのLANGUAGE_OPTIONSと同じ）
+const JAPANESE_VOICE_ID: &str = "fb25b315-dfba-444f-b99d-4c8535672cb7";
+const ENGLISH_VOICE_ID: &str = "6064bbbb-72de-437a-9a6e-aca16d123e02";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TTSConfig {
     pub model_id: String,
     pub voice_id: String,
-    pub speed: f32,
+    pub speed: f32,  // 再生速度（0.5〜3.0, ピッチを保ったままローカルで変換）
     pub volume: f32,
     pub language: String,
     pub voice_speed: f32,  // Cartesia API speed parameter (-1.0 to 1.0)
+    #[serde(default)]
+    pub voice_effects: HashMap<String, Vec<EffectConfig>>,  // voice_idごとのエフェクト設定
 }
 
 impl Default for TTSConfig {
     fn default() -> Self {
         Self {
             model_id: String::from("sonic-2"),
-            voice_id: String::from("fb25b315-dfba-444f-b99d-4c8535672cb7"), // Japanese voice
+            voice_id: String::from(JAPANESE_VOICE_ID), // Japanese voice
             speed: 1.0,
             volume: 1.0,
             language: String::from("ja"),
             voice_speed: 0.0,  // Default normal speed
+            voice_effects: HashMap::new(),
         }
     }
 }
@@ -40,15 +50,13 @@ impl TTSConfig {
         Self::default()
     }
 
-    #[allow(dead_code)]
     pub fn with_voice_id(mut self, voice_id: String) -> Self {
         self.voice_id = voice_id;
         self
     }
 
-    #[allow(dead_code)]
     pub fn with_speed(mut self, speed: f32) -> Self {
-        self.speed = speed.clamp(0.5, 2.0);
+        self.speed = clamp_speed(speed);
         self
     }
 
@@ -58,11 +66,26 @@ impl TTSConfig {
         self
     }
 
-    #[allow(dead_code)]
     pub fn with_voice_speed(mut self, voice_speed: f32) -> Self {
         self.voice_speed = voice_speed.clamp(-1.0, 1.0);
         self
     }
+
+    /// 言語を切り替え、その言語の既定の声を使用する
+    pub fn with_language(mut self, language: &str) -> Self {
+        match language {
+            "ja" => self.voice_id = JAPANESE_VOICE_ID.to_string(),
+            "en" => self.voice_id = ENGLISH_VOICE_ID.to_string(),
+            _ => {}
+        }
+        self.language = language.to_string();
+        self
+    }
+
+    /// 現在の声に設定されたエフェクトを取得
+    pub fn effects_for_voice(&self) -> Vec<EffectConfig> {
+        self.voice_effects.get(&self.voice_id).cloned().unwrap_or_default()
+    }
 }
 
 pub struct ApiKeyManager;
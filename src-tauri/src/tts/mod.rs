@@ -7,5 +7,6 @@ pub mod client;
 pub mod config;
 pub mod error;
 pub mod storage;
+pub mod synthesizer;
 
 pub use config::TTSConfig;
\ No newline at end of file
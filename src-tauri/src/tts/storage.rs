@@ -8,25 +8,30 @@ use std::path::PathBuf;
 use super::error::{TTSError, TTSResult};
 
 const API_KEY_FILE: &str = "api_key.dat";
+const HTTP_TOKEN_FILE: &str = "http_token.dat";
+
+/// アプリケーションのデータディレクトリ（ホームディレクトリの.Taneyomi-kun）
+pub fn app_data_dir() -> TTSResult<PathBuf> {
+    let home_dir = std::env::var("HOME")
+        .or_else(|_| std::env::var("USERPROFILE"))
+        .map_err(|_| TTSError::ConfigError("ホームディレクトリが見つかりません".to_string()))?;
+        
+    let app_dir = PathBuf::from(home_dir).join(".Taneyomi-kun");
+    
+    // ディレクトリが存在しない場合は作成
+    if !app_dir.exists() {
+        fs::create_dir_all(&app_dir)
+            .map_err(|e| TTSError::ConfigError(format!("ディレクトリの作成に失敗: {}", e)))?;
+    }
+    
+    Ok(app_dir)
+}
 
 pub struct ApiKeyStorage;
 
 impl ApiKeyStorage {
     fn get_storage_path() -> TTSResult<PathBuf> {
-        // ホームディレクトリの.Taneyomi-kun以下に保存
-        let home_dir = std::env::var("HOME")
-            .or_else(|_| std::env::var("USERPROFILE"))
-            .map_err(|_| TTSError::ConfigError("ホームディレクトリが見つかりません".to_string()))?;
-            
-        let app_dir = PathBuf::from(home_dir).join(".Taneyomi-kun");
-        
-        // ディレクトリが存在しない場合は作成
-        if !app_dir.exists() {
-            fs::create_dir_all(&app_dir)
-                .map_err(|e| TTSError::ConfigError(format!("ディレクトリの作成に失敗: {}", e)))?;
-        }
-        
-        Ok(app_dir.join(API_KEY_FILE))
+        Ok(app_data_dir()?.join(API_KEY_FILE))
     }
     
     pub fn save_api_key(api_key: &str) -> TTSResult<()> {
@@ -86,4 +91,36 @@ impl ApiKeyStorage {
         let decrypted: Vec<u8> = data.iter().map(|&b| b ^ key).collect();
         String::from_utf8_lossy(&decrypted).to_string()
     }
-}
\ No newline at end of file
+}
+
+/// HTTPサーバーのアクセストークン（APIキーと同じ方法で保存）
+pub struct HttpTokenStorage;
+
+impl HttpTokenStorage {
+    fn get_storage_path() -> TTSResult<PathBuf> {
+        Ok(app_data_dir()?.join(HTTP_TOKEN_FILE))
+    }
+
+    /// 保存されたトークンを読み込む（初回は生成して保存する）
+    pub fn get_or_create() -> TTSResult<String> {
+        let path = Self::get_storage_path()?;
+        if path.exists() {
+            let encrypted = fs::read(&path)
+                .map_err(|e| TTSError::ConfigError(format!("アクセストークンの読み込みに失敗: {}", e)))?;
+            let token = ApiKeyStorage::simple_decrypt(&encrypted);
+            if !token.trim().is_empty() {
+                return Ok(token);
+            }
+        }
+        Self::regenerate()
+    }
+
+    /// 新しいトークンを生成して保存する
+    pub fn regenerate() -> TTSResult<String> {
+        let path = Self::get_storage_path()?;
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        fs::write(&path, ApiKeyStorage::simple_encrypt(&token))
+            .map_err(|e| TTSError::ConfigError(format!("アクセストークンの保存に失敗: {}", e)))?;
+        Ok(token)
+    }
+}
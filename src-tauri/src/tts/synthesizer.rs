@@ -0,0 +1,45 @@
+/**
+ * 再生を伴わない音声合成
+ * Cartesia APIからの音声を処理パイプラインに通してメモリ上に書き出す
+ */
+
+use tokio::sync::{mpsc, oneshot};
+
+use crate::audio::config::AudioConfig;
+use crate::audio::effects::EffectConfig;
+use crate::audio::pipeline::{decode_pcm_f32le, UtterancePipeline};
+use crate::audio::stretch::TimeStretcher;
+
+use super::client::CartesiaClient;
+use super::config::TTSConfig;
+use super::error::TTSResult;
+
+/// テキストを合成し、エフェクトとラウドネス正規化、再生速度を適用した音声を返す
+pub async fn render_speech(
+    config: TTSConfig,
+    api_key: String,
+    text: &str,
+    effects: &[EffectConfig],
+    audio_config: &AudioConfig,
+) -> TTSResult<Vec<f32>> {
+    let speed = config.speed;
+    let (audio_tx, mut audio_rx) = mpsc::channel::<Vec<u8>>(100);
+    // キャンセルはしないが、送信側を保持しておく
+    let (_cancel_tx, cancel_rx) = oneshot::channel::<()>();
+    let client = CartesiaClient::new_with_api_key(config, api_key);
+    let mut pipeline = UtterancePipeline::new(effects, audio_config);
+
+    let synthesis = client.synthesize_speech(text, audio_tx, cancel_rx);
+    let collect = async {
+        let mut samples = Vec::new();
+        while let Some(audio_data) = audio_rx.recv().await {
+            samples.extend(pipeline.process(decode_pcm_f32le(&audio_data)));
+        }
+        samples
+    };
+
+    let (result, mut samples) = tokio::join!(synthesis, collect);
+    result?;
+    samples.extend(pipeline.finish());
+    Ok(TimeStretcher::stretch_all(&samples, speed))
+}
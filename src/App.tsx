@@ -39,6 +39,8 @@ function App() {
   const historyStore = useHistoryStore()
   const languageStore = useLanguageStore()
   const hasApiKeyRef = useRef(false)
+  // 読み上げID → 履歴IDの対応（再生完了時に履歴の状態を更新する）
+  const historyIdsRef = useRef(new Map<string, string>())
 
   // hasApiKeyの変更をrefに反映
   useEffect(() => {
@@ -53,11 +55,15 @@ function App() {
     setHasApiKey(exists)
   }
 
-  const playText = async (textToPlay: string, overrideLanguage?: string) => {
+  const playText = async (
+    textToPlay: string,
+    overrideLanguage?: string,
+    effects?: tts.EffectConfig[],
+  ): Promise<string | undefined> => {
     if (!hasApiKeyRef.current) {
       window.alert('APIキーを設定してください')
       setShowSettings(true)
-      return
+      return undefined
     }
 
     try {
@@ -86,9 +92,10 @@ function App() {
       // Rust側で音声再生を行うため、AudioPlayerの初期化は不要
       log('Using Rust audio player, volume set to:', volume / 100)
 
-      log('Starting TTS synthesis')
-      await tts.synthesizeSpeech(textToPlay)
-      log('TTS synthesis command sent')
+      log('Enqueueing TTS request')
+      const id = await tts.synthesizeSpeech(textToPlay, effects)
+      log('TTS request enqueued:', id)
+      return id
     } catch (err) {
       error('読み上げエラー:', err)
       window.alert(`読み上げ中にエラーが発生しました: ${err}`)
@@ -108,8 +115,10 @@ function App() {
     const historyItem = historyStore.addItem(text)
 
     try {
-      await playText(text)
-      historyStore.updateStatus(historyItem.id, 'completed')
+      const id = await playText(text)
+      if (id) {
+        historyIdsRef.current.set(id, historyItem.id)
+      }
     } catch (err) {
       historyStore.updateStatus(historyItem.id, 'error')
       throw err
@@ -157,6 +166,22 @@ function App() {
         setIsPlaying(false)
       })
 
+      // 読み上げが終わったら履歴の状態を更新する
+      const unlistenPlaybackFinished = await listen<{
+        utterance_id: string
+        cancelled: boolean
+      }>('playback-finished', (event) => {
+        const historyId = historyIdsRef.current.get(event.payload.utterance_id)
+        if (historyId) {
+          historyIdsRef.current.delete(event.payload.utterance_id)
+          // スキップ・停止された読み上げは再生し終えたものと分けて表示する
+          historyStore.updateStatus(
+            historyId,
+            event.payload.cancelled ? 'cancelled' : 'completed',
+          )
+        }
+      })
+
       const unlistenAudioError = await listen<string>(
         'audio-error',
         (event) => {
@@ -167,70 +192,43 @@ function App() {
       )
 
       // HTTPリクエストのイベントリスナー
+      // 読み上げはRust側のキューが行うため、ここでは履歴への追加のみ行う
       log('Setting up http-tts-request listener')
       const unlistenHttpRequest = await listen<{
+        id: string
         text: string
         priority?: string
         voice_speed?: number
         language?: string
-      }>('http-tts-request', async (event) => {
-        window.console.log('[App] Received HTTP TTS request:', event.payload)
-        window.console.log('[App] Current voiceSpeed:', voiceSpeed)
+        effects?: tts.EffectConfig[]
+      }>('http-tts-request', (event) => {
         log('Received HTTP TTS request:', event.payload)
-        log('Current voiceSpeed:', voiceSpeed)
-
-        // 履歴に追加
         const historyItem = historyStore.addItem(event.payload.text)
-        window.console.log('[App] Added to history:', historyItem)
-        window.console.log('[App] Has API key:', hasApiKeyRef.current)
-
-        // HTTPリクエストからvoice_speedを一時的に設定（未指定の場合は現在の設定値を使用）
-        const originalVoiceSpeed = voiceSpeed
-        if (
-          event.payload.voice_speed !== undefined &&
-          event.payload.voice_speed !== null
-        ) {
-          setVoiceSpeed(event.payload.voice_speed)
-        }
-
-        // 即座に読み上げ開始（現在の読み上げがあれば中断）
-        try {
-          // 現在再生中の音声を停止
-          window.console.log('[App] Stopping current playback...')
-          await tts.stopSpeech()
-          setIsPlaying(false)
-
-          // 少し待機してから新しい読み上げを開始
-          await new Promise((resolve) => window.setTimeout(resolve, 100))
-
-          window.console.log('[App] Starting new playback...')
-          // HTTPリクエストで言語が指定されている場合はそれを使用
-          await playText(event.payload.text, event.payload.language)
-          window.console.log('[App] Playback completed')
-          historyStore.updateStatus(historyItem.id, 'completed')
-        } catch (err) {
-          window.console.error('[App] Playback error:', err)
-          error('Playback error:', err)
-          historyStore.updateStatus(historyItem.id, 'error')
-        } finally {
-          // 元の設定に戻す
-          if (
-            event.payload.voice_speed !== undefined &&
-            event.payload.voice_speed !== null
-          ) {
-            setVoiceSpeed(originalVoiceSpeed)
-          }
-        }
+        historyIdsRef.current.set(event.payload.id, historyItem.id)
+        setIsPlaying(true)
       })
       log('http-tts-request listener registered successfully')
 
+      // 予約読み上げのイベントリスナー（履歴への追加のみ）
+      const unlistenScheduleFired = await listen<{
+        job_id: string
+        id: string
+        text: string
+      }>('schedule-fired', (event) => {
+        const historyItem = historyStore.addItem(event.payload.text)
+        historyIdsRef.current.set(event.payload.id, historyItem.id)
+        setIsPlaying(true)
+      })
+
       // クリーンアップ
       return () => {
         log('Cleaning up event listeners')
         unlistenAudioChunk()
         unlistenAudioComplete()
+        unlistenPlaybackFinished()
         unlistenAudioError()
         unlistenHttpRequest()
+        unlistenScheduleFired()
         // Rust側での音声再生を使用するため、AudioPlayerのクリーンアップは不要
       }
     }
@@ -273,7 +271,7 @@ function App() {
             <button
               className="btn btn-primary icon-btn"
               onClick={handlePlay}
-              disabled={isPlaying || !text.trim()}
+              disabled={!text.trim()}
               title="読み上げ開始"
             >
               <Play size={24} />
@@ -317,13 +315,12 @@ function App() {
           historyStore.removeItem(id)
         }}
         onReplay={async (item) => {
-          if (isPlaying) {
-            await handleStop()
-          }
           const newItem = historyStore.addItem(item.text)
           try {
-            await playText(item.text)
-            historyStore.updateStatus(newItem.id, 'completed')
+            const id = await playText(item.text)
+            if (id) {
+              historyIdsRef.current.set(id, newItem.id)
+            }
           } catch {
             historyStore.updateStatus(newItem.id, 'error')
           }
@@ -31,6 +31,8 @@ export const HistoryPanel: React.FC<HistoryPanelProps> = ({
     switch (status) {
       case 'completed':
         return '✓'
+      case 'cancelled':
+        return '–'
       case 'error':
         return '✕'
       case 'processing':
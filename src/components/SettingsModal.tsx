@@ -27,6 +27,9 @@ export function SettingsModal({
   const [hasApiKey, setHasApiKey] = useState(false)
   const [httpPort, setHttpPort] = useState(50080)
   const [httpEnabled, setHttpEnabled] = useState(true)
+  const [httpRequireToken, setHttpRequireToken] = useState(false)
+  // 許可するオリジン（1行に1つ）
+  const [httpAllowedOrigins, setHttpAllowedOrigins] = useState('')
 
   useEffect(() => {
     if (isOpen) {
@@ -36,10 +39,17 @@ export function SettingsModal({
       })
 
       // HTTPサーバー設定の読み込み
-      invoke<{ port: number; enabled: boolean }>('get_http_config')
+      invoke<{
+        port: number
+        enabled: boolean
+        require_token: boolean
+        allowed_origins: string[]
+      }>('get_http_config')
         .then((config) => {
           setHttpPort(config.port)
           setHttpEnabled(config.enabled)
+          setHttpRequireToken(config.require_token)
+          setHttpAllowedOrigins(config.allowed_origins.join('\n'))
         })
         .catch((err) => {
           error('Failed to load HTTP config:', err)
@@ -72,10 +82,21 @@ export function SettingsModal({
 
   const handleSaveHttpConfig = async () => {
     try {
-      log('Saving HTTP config', { port: httpPort, enabled: httpEnabled })
+      const allowedOrigins = httpAllowedOrigins
+        .split('\n')
+        .map((origin) => origin.trim())
+        .filter((origin) => origin !== '')
+      log('Saving HTTP config', {
+        port: httpPort,
+        enabled: httpEnabled,
+        requireToken: httpRequireToken,
+        allowedOrigins,
+      })
       await invoke('update_http_config', {
         port: httpPort,
         enabled: httpEnabled,
+        requireToken: httpRequireToken,
+        allowedOrigins,
       })
       window.alert('HTTPサーバー設定を保存しました')
     } catch (err) {
@@ -238,6 +259,53 @@ export function SettingsModal({
               HTTPサーバーを有効にする
             </label>
           </div>
+          <div style={{ marginBottom: '12px' }}>
+            <label
+              style={{
+                display: 'flex',
+                alignItems: 'center',
+                fontSize: '0.9em',
+                cursor: 'pointer',
+              }}
+            >
+              <input
+                id="http-require-token"
+                type="checkbox"
+                checked={httpRequireToken}
+                onChange={(e) => setHttpRequireToken(e.target.checked)}
+                disabled={!httpEnabled}
+                style={{ marginRight: '8px' }}
+              />
+              アクセストークンを必須にする
+            </label>
+          </div>
+          <div style={{ marginBottom: '12px' }}>
+            <label
+              htmlFor="http-allowed-origins"
+              style={{
+                display: 'block',
+                fontSize: '0.9em',
+                marginBottom: '4px',
+              }}
+            >
+              ブラウザからのアクセスを許可するオリジン（1行に1つ）:
+            </label>
+            <textarea
+              id="http-allowed-origins"
+              value={httpAllowedOrigins}
+              onChange={(e) => setHttpAllowedOrigins(e.target.value)}
+              disabled={!httpEnabled}
+              placeholder="https://example.com"
+              rows={3}
+              className="input-field"
+              style={{ width: '100%', boxSizing: 'border-box' }}
+            />
+            <p style={{ fontSize: '0.8em', color: '#666', marginTop: '4px' }}>
+              ブラウザ拡張・OBSのブラウザソース・Webのコメントビューアーなどは、
+              ここに登録するまでアクセスを断ります（以前のバージョンから変わりました）。
+              拡張機能は chrome-extension://&lt;ID&gt; の形式で登録できます
+            </p>
+          </div>
           <div style={{ display: 'flex', gap: '8px', alignItems: 'center' }}>
             <label htmlFor="http-port" style={{ fontSize: '0.9em' }}>
               ポート:
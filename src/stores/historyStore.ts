@@ -9,7 +9,7 @@ export interface HistoryItem {
   id: string
   text: string
   timestamp: Date
-  status: 'completed' | 'error' | 'processing'
+  status: 'completed' | 'cancelled' | 'error' | 'processing'
 }
 
 interface HistoryStore {
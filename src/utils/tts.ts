@@ -14,6 +14,15 @@ export interface TTSConfig {
   voice_speed: number
 }
 
+/** 音声エフェクト設定（Rust側の EffectConfig に対応） */
+export type EffectConfig =
+  | { type: 'pitch_shift'; semitones: number }
+  | { type: 'echo'; delay_ms: number; feedback: number; mix: number }
+  | { type: 'reverb'; room_size: number; damping: number; mix: number }
+  | { type: 'equalizer'; low_db: number; mid_db: number; high_db: number }
+  | { type: 'robot'; frequency_hz: number; mix: number }
+  | { type: 'telephone' }
+
 export async function setApiKey(apiKey: string): Promise<void> {
   await invoke('set_api_key', { apiKey })
 }
@@ -43,10 +52,300 @@ export async function getTTSConfig(): Promise<TTSConfig> {
   return await invoke('get_tts_config')
 }
 
-export async function synthesizeSpeech(text: string): Promise<void> {
-  await invoke('synthesize_speech', { text })
+export async function setVoiceEffects(
+  voiceId: string,
+  effects: EffectConfig[],
+): Promise<void> {
+  await invoke('set_voice_effects', { voiceId, effects })
+}
+
+export type Priority = 'low' | 'normal' | 'high'
+
+/** 読み上げキューの要素（Rust側の QueueItem に対応） */
+export interface QueueItem {
+  id: string
+  text: string
+  source: 'app' | 'http' | 'schedule' | 'bouyomi'
+  /** 送信者（HTTPで指定された場合） */
+  sender?: string | null
+  priority: Priority
+  /** 読み上げるレーン */
+  lane?: string | null
+  language?: string | null
+  voice_id?: string | null
+  voice_speed?: number | null
+  effects?: EffectConfig[] | null
+  /** 音量（0〜1, 未指定なら1） */
+  volume?: number | null
+  enqueued_at: number
+  sequence: number
+  /** 溢れて捨てた読み上げの件数（「ほか N 件」の読み上げのみ） */
+  summarized: number
+}
+
+export interface QueueSnapshot {
+  /** 一時停止中か */
+  paused: boolean
+  /** レーンごとの読み上げ中の読み上げ */
+  current: QueueItem[]
+  /** 全レーンの待機中の読み上げ（読み上げる順） */
+  pending: QueueItem[]
+  /** 重ねて読み上げ中・待機中の割り込み */
+  overlay: QueueItem[]
+}
+
+/** 読み上げキューに積む（読み上げIDを返す） */
+export async function synthesizeSpeech(
+  text: string,
+  effects?: EffectConfig[],
+  priority?: Priority,
+  lane?: string,
+): Promise<string> {
+  return await invoke('synthesize_speech', { text, effects, priority, lane })
+}
+
+export async function getSpeechQueue(): Promise<QueueSnapshot> {
+  return await invoke('get_speech_queue')
+}
+
+export type SpeechState =
+  | 'queued'
+  | 'synthesizing'
+  | 'playing'
+  | 'finished'
+  | 'failed'
+  | 'cancelled'
+
+/** 読み上げの進み具合（speech-status イベントでも通知される, 時刻はUNIXエポックからのミリ秒） */
+export interface SpeechStatus {
+  id: string
+  state: SpeechState
+  text: string
+  source: 'app' | 'http' | 'schedule' | 'bouyomi'
+  lane?: string | null
+  queued_at: number
+  synthesizing_at?: number | null
+  playing_at?: number | null
+  /** 読み上げ終えた・失敗した・取り消された時刻 */
+  finished_at?: number | null
+  /** 失敗・取り消しの理由 */
+  error?: string | null
+}
+
+/** 読み上げの進み具合を取得する（記録が残っていなければnull） */
+export async function getSpeechStatus(id: string): Promise<SpeechStatus | null> {
+  return await invoke('get_speech_status', { id })
+}
+
+/** 優先度の高い読み上げが届いたときの、読み上げ中の読み上げの扱い */
+export type PreemptPolicy =
+  | 'wait'
+  | 'interrupt_resume'
+  | 'interrupt_drop'
+  | 'overlay'
+
+/** 再生レーン（レーンごとに別々のキューで読み上げ、ほかのレーンと重ねて再生する） */
+export interface LaneConfig {
+  name: string
+  /** 声・言語・話速の既定値（読み上げごとの指定が優先） */
+  voice_id?: string | null
+  language?: string | null
+  voice_speed?: number | null
+  /** 音量（0〜1, 全体の音量に掛ける） */
+  volume: number
+}
+
+/** 読み上げキューの設定（Rust側の QueueConfig に対応） */
+export interface QueueConfig {
+  persistence: {
+    enabled: boolean
+    /** 復元時にこの秒数より古い読み上げは破棄（0で無制限） */
+    max_age_secs: number
+  }
+  /** 届いた読み上げの優先度ごとの割り込み方 */
+  preemption: {
+    normal: PreemptPolicy
+    high: PreemptPolicy
+  }
+  /** 連投・荒らし対策（HTTPから届いた読み上げにのみ適用） */
+  filter: {
+    enabled: boolean
+    /** 同じ・よく似たメッセージを除外する期間（秒, 0で無効） */
+    duplicate_window_secs: number
+    /** よく似ているとみなす類似度（0.5〜1） */
+    similarity: number
+    /** 送信者ごとの1分あたりの上限（0で無制限） */
+    max_per_minute: number
+    /** 同じ送信者の続けざまのメッセージを1件にまとめる間隔（ミリ秒, 0で無効） */
+    merge_window_ms: number
+    max_merged_chars: number
+  }
+  /** 溜まりすぎへの対策 */
+  backlog: {
+    /** 待機中の読み上げの上限（0で無制限） */
+    max_length: number
+    overflow: 'drop_oldest' | 'drop_lowest' | 'summarize'
+    /** 溜まり具合に応じて早口・短縮して追いつく */
+    catch_up: {
+      enabled: boolean
+      start_at: number
+      full_at: number
+      /** 最も早口にしたときの速度の倍率（1〜3） */
+      max_speed: number
+      /** 追いついている間に短縮する文字数（0で短縮しない） */
+      truncate_chars: number
+    }
+  }
+  /** 読み上げ中に次の読み上げを合成しておく */
+  prefetch: {
+    enabled: boolean
+    /** 先読みする件数（1〜2） */
+    count: number
+    /** 先読みした音声を溜めておく上限（MB） */
+    max_memory_mb: number
+  }
+  /** 再生レーン（先頭がレーンを指定しない読み上げのレーン） */
+  lanes: LaneConfig[]
+}
+
+export type FilterReason = 'duplicate' | 'throttled' | 'merged'
+
+/** 連投・荒らし対策の集計 */
+export interface FilterReport {
+  duplicates: number
+  throttled: number
+  merged: number
+  /** 直近に除外・統合したメッセージ（新しい順） */
+  recent: {
+    reason: FilterReason
+    text: string
+    sender: string | null
+    at: number
+  }[]
+}
+
+/** 起動時に復元できなかった読み上げ */
+export interface LostQueueItem {
+  reason: 'expired' | 'overflow'
+  lost_at: number
+  item: QueueItem
+}
+
+export interface RestoreReport {
+  restored: number
+  lost: LostQueueItem[]
+  error: string | null
+}
+
+export async function getQueueConfig(): Promise<QueueConfig> {
+  return await invoke('get_queue_config')
+}
+
+export async function updateQueueConfig(config: QueueConfig): Promise<void> {
+  await invoke('update_queue_config', { config })
+}
+
+export async function getQueueRestoreReport(): Promise<RestoreReport | null> {
+  return await invoke('get_queue_restore_report')
+}
+
+export async function getQueueFilterReport(): Promise<FilterReport> {
+  return await invoke('get_queue_filter_report')
+}
+
+/** 読み上げる時刻の決め方（時刻はPCのローカル時刻） */
+export type Schedule =
+  /** 1回限り（UNIXミリ秒, 追加時は "21:00" などの表記も可） */
+  | { type: 'once'; at: number | string }
+  /** 0時から数えて every_minutes 分ごと（offset_minutes 分ずらす） */
+  | { type: 'interval'; every_minutes: number; offset_minutes?: number }
+
+/** 予約した読み上げ（Rust側の ScheduledJob に対応） */
+export interface ScheduledJob {
+  id: string
+  name: string | null
+  schedule: Schedule
+  request: Omit<QueueItem, 'id' | 'enqueued_at' | 'sequence' | 'summarized'>
+  enabled: boolean
+  next_at: number | null
+  last_at: number | null
+  /** 取り込んだ予定表のアラーム */
+  origin: string | null
+}
+
+export interface NewSchedule {
+  name?: string
+  schedule: Schedule
+  enabled?: boolean
+  /** 本文の {hour}・{minute} は読み上げる時刻に置き換わる */
+  text: string
+  priority?: Priority
+  voice_id?: string
+  effects?: EffectConfig[]
+}
+
+export async function getSchedules(): Promise<ScheduledJob[]> {
+  return await invoke('get_schedules')
+}
+
+export async function addSchedule(schedule: NewSchedule): Promise<ScheduledJob> {
+  return await invoke('add_schedule', { schedule })
+}
+
+export async function removeSchedule(id: string): Promise<void> {
+  await invoke('remove_schedule', { id })
+}
+
+export async function setScheduleEnabled(
+  id: string,
+  enabled: boolean,
+): Promise<ScheduledJob> {
+  return await invoke('set_schedule_enabled', { id, enabled })
+}
+
+/** 予定表ファイル（.ics）のアラームを予約として取り込む */
+export async function importIcsFile(
+  path: string,
+): Promise<{ imported: number; skipped: number; unknown_timezone: number }> {
+  return await invoke('import_ics_file', { path })
+}
+
+export type ExportFormat = 'wav' | 'flac'
+
+/** 再生せずに音声ファイルへ書き出す（書き出したバイト数を返す） */
+export async function synthesizeToFile(
+  text: string,
+  path: string,
+  format: ExportFormat = 'wav',
+  effects?: EffectConfig[],
+): Promise<number> {
+  return await invoke('synthesize_to_file', { text, path, format, effects })
+}
+
+export async function stopSpeech(soft = false): Promise<void> {
+  await invoke('stop_speech', { soft })
+}
+
+/** レーン（未指定なら既定のレーン）の読み上げをスキップする */
+export async function skipSpeech(lane?: string): Promise<void> {
+  await invoke('skip_speech', { lane })
+}
+
+/** 読み上げ中の音声を一時停止し、次の読み上げも始めない */
+export async function pauseSpeech(): Promise<void> {
+  await invoke('pause_speech')
+}
+
+export async function resumeSpeech(): Promise<void> {
+  await invoke('resume_speech')
+}
+
+/** 読み上げを1件取り消す（読み上げ中なら止める, 見つからなければ false） */
+export async function cancelSpeech(id: string): Promise<boolean> {
+  return await invoke('cancel_speech', { id })
 }
 
-export async function stopSpeech(): Promise<void> {
-  await invoke('stop_speech')
+/** 待機中の読み上げを全て取り除く（取り除いた件数を返す） */
+export async function clearSpeechQueue(): Promise<number> {
+  return await invoke('clear_speech_queue')
 }